@@ -0,0 +1,207 @@
+//! The known answers for a year, used to catch regressions.
+//!
+//! Each year keeps an `answers` file next to its `Cargo.toml` with one
+//! tab-separated `day part answer` entry per line. Answers the puzzle site
+//! rejected have the reason it gave as a fourth field, e.g. `too high`. Blank
+//! lines and lines starting with `#` are ignored. Newlines, tabs and
+//! backslashes in answers are escaped as `\n`, `\t` and `\\`.
+
+use crate::solution::Part;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, Part), String>,
+    /// Each rejected answer with the reason it was rejected.
+    rejected: BTreeMap<(u8, Part), BTreeMap<String, String>>,
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            other => {
+                return Err(format!(
+                    "Unknown escape sequence \\{}",
+                    other.unwrap_or(' ')
+                ))
+            }
+        }
+    }
+
+    Ok(unescaped)
+}
+
+impl Answers {
+    pub fn new() -> Self {
+        Answers::default()
+    }
+
+    /// Reads an answers file, a missing file has no answers.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(s) => s
+                .parse()
+                .map_err(|e| format!("{}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::new()),
+            Err(e) => Err(format!("{}: {}", path.display(), e).into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: Part, answer: &str) {
+        self.answers.insert((day, part), answer.to_string());
+    }
+
+    /// Notes that `answer` is wrong, so it isn't submitted again.
+    pub fn reject(&mut self, day: u8, part: Part, answer: &str, reason: &str) {
+        self.rejected
+            .entry((day, part))
+            .or_default()
+            .insert(answer.to_string(), reason.to_string());
+    }
+
+    /// Why `answer` was rejected, if it was.
+    pub fn rejection(&self, day: u8, part: Part, answer: &str) -> Option<&str> {
+        self.rejected
+            .get(&(day, part))?
+            .get(answer)
+            .map(String::as_str)
+    }
+
+    /// The number of known answers, not counting rejected ones.
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+impl FromStr for Answers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::new();
+
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let error = |e: &dyn std::fmt::Display| format!("line {}: {}: {:?}", i + 1, e, line);
+
+            if fields.len() != 3 && fields.len() != 4 {
+                return Err(error(&"Expected day, part, answer and an optional reason"));
+            }
+
+            let day = fields[0].trim().parse().map_err(|e| error(&e))?;
+            let part = fields[1].parse().map_err(|e| error(&e))?;
+            let answer = unescape(fields[2]).map_err(|e| error(&e))?;
+
+            match fields.get(3) {
+                Some(reason) => answers.reject(day, part, &answer, reason),
+                None => answers.insert(day, part, &answer),
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for ((day, part), answer) in &self.answers {
+            writeln!(f, "{}\t{}\t{}", day, part, escape(answer))?;
+        }
+
+        for ((day, part), rejected) in &self.rejected {
+            for (answer, reason) in rejected {
+                writeln!(f, "{}\t{}\t{}\t{}", day, part, escape(answer), reason)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() -> Result<(), String> {
+        let mut answers = Answers::new();
+        answers.insert(8, Part::Two, "# #\n#\t\\");
+        answers.insert(1, Part::One, "3402609");
+        answers.reject(1, Part::Two, "5103", "too low");
+
+        let s = answers.to_string();
+        assert_eq!(
+            s,
+            "1\t1\t3402609\n8\t2\t# #\\n#\\t\\\\\n1\t2\t5103\ttoo low\n"
+        );
+        assert_eq!(s.parse::<Answers>()?, answers);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse() -> Result<(), String> {
+        let answers: Answers = "# Comment\n\n2\t1\t42\n".parse()?;
+        assert_eq!(answers.get(2, Part::One), Some("42"));
+        assert_eq!(answers.get(2, Part::Two), None);
+
+        let answers: Answers = "2\t2\t17\twrong\n".parse()?;
+        assert_eq!(answers.get(2, Part::Two), None);
+        assert_eq!(answers.rejection(2, Part::Two, "17"), Some("wrong"));
+        assert_eq!(answers.rejection(2, Part::Two, "18"), None);
+        assert!(answers.is_empty());
+
+        assert!("1\t3\t42".parse::<Answers>().is_err());
+        assert!("1\t1".parse::<Answers>().is_err());
+        assert!("x\t1\t42".parse::<Answers>().is_err());
+        assert!("1\t1\t\\q".parse::<Answers>().is_err());
+
+        Ok(())
+    }
+}
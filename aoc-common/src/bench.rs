@@ -0,0 +1,233 @@
+//! Timing of parsing and each part over repeated runs.
+
+use crate::runner::{self, format_elapsed, isolate};
+use crate::solution::{Day, Part};
+use std::error::Error;
+use std::fmt::Write as _;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// How many times each stage is run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    /// Untimed runs before measuring, to warm caches and the allocator.
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            warmup: 3,
+            iterations: 10,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Panics if `samples` is empty.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Stats {
+            median,
+            min: samples[0],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// What was timed in a `Measurement`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+#[derive(Debug)]
+pub struct Measurement {
+    pub year: u16,
+    pub day: u8,
+    pub stage: Stage,
+    pub stats: Result<Stats, String>,
+}
+
+fn time<T, E>(settings: Settings, mut f: impl FnMut() -> Result<T, E>) -> Result<Stats, E> {
+    for _ in 0..settings.warmup {
+        black_box(f()?);
+    }
+
+    let mut samples = Vec::with_capacity(settings.iterations);
+
+    for _ in 0..settings.iterations.max(1) {
+        let start = Instant::now();
+        let result = f()?;
+        samples.push(start.elapsed());
+        black_box(result);
+    }
+
+    Ok(Stats::from_samples(samples))
+}
+
+/// Times parsing `input` and then solving each of `parts` from a single
+/// parsed input. A stage that panics is recorded as an error.
+pub fn bench_day(day: &Day, input: &str, parts: &[Part], settings: Settings) -> Vec<Measurement> {
+    let measurement = |stage, stats: Result<Result<Stats, Box<dyn Error>>, String>| Measurement {
+        year: day.year,
+        day: day.day,
+        stage,
+        stats: stats.and_then(|stats| stats.map_err(|e| e.to_string())),
+    };
+
+    let mut measurements = vec![measurement(
+        Stage::Parse,
+        isolate(|| time(settings, || day.parse(input))),
+    )];
+
+    if let Ok(Ok(parsed)) = isolate(|| day.parse(input)) {
+        for &part in parts {
+            let stats = isolate(|| time(settings, || day.solve(&parsed, part)));
+            measurements.push(measurement(Stage::Solve(part), stats));
+        }
+    }
+
+    measurements
+}
+
+pub fn table(measurements: &[Measurement]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Year  Day  Stage      Median        Min        Max");
+    let _ = writeln!(out, "----  ---  ------  ---------  ---------  ---------");
+
+    for m in measurements {
+        let stage = match m.stage {
+            Stage::Parse => "parse".to_string(),
+            Stage::Solve(part) => format!("part {}", part),
+        };
+
+        let prefix = format!("{:>4}  {:>3}  {:6}  ", m.year, m.day, stage);
+        let text = match &m.stats {
+            Ok(stats) => format!(
+                "{:>9}  {:>9}  {:>9}",
+                format_elapsed(stats.median),
+                format_elapsed(stats.min),
+                format_elapsed(stats.max)
+            ),
+            // Panic messages can span several lines.
+            Err(e) => format!("error: {}", e),
+        };
+        runner::row(&mut out, &prefix, &text);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Registry, Solution};
+    use std::cell::Cell;
+
+    #[test]
+    fn stats() {
+        let ms = Duration::from_millis;
+
+        let odd = Stats::from_samples(vec![ms(5), ms(1), ms(3)]);
+        assert_eq!(
+            odd,
+            Stats {
+                median: ms(3),
+                min: ms(1),
+                max: ms(5)
+            }
+        );
+
+        let even = Stats::from_samples(vec![ms(4), ms(1), ms(2), ms(9)]);
+        assert_eq!(even.median, ms(3));
+    }
+
+    #[test]
+    fn warmup() {
+        let calls = Cell::new(0);
+        let settings = Settings {
+            warmup: 2,
+            iterations: 5,
+        };
+
+        time(settings, || -> Result<(), ()> {
+            calls.set(calls.get() + 1);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(calls.get(), 7);
+
+        assert_eq!(time(settings, || Err::<(), _>("failed")), Err("failed"));
+    }
+
+    struct Length;
+
+    impl Solution for Length {
+        type Input = String;
+        type Answer1 = usize;
+        type Answer2 = usize;
+
+        fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            if input.is_empty() {
+                return Err("Empty".into());
+            }
+            Ok(input.to_string())
+        }
+
+        fn part1(input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+            Ok(input.len())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+            if input == "panic" {
+                panic!("Broken");
+            }
+            Err("Not solved".into())
+        }
+    }
+
+    #[test]
+    fn measurements() {
+        let mut registry = Registry::new();
+        registry.add::<Length>(2019, 1, "abc");
+        let day = registry.get(2019, 1).unwrap();
+
+        let measurements = bench_day(day, "abc", &Part::ALL, Settings::default());
+        let stages: Vec<Stage> = measurements.iter().map(|m| m.stage).collect();
+        assert_eq!(
+            stages,
+            vec![
+                Stage::Parse,
+                Stage::Solve(Part::One),
+                Stage::Solve(Part::Two)
+            ]
+        );
+        assert!(measurements[1].stats.is_ok());
+        assert!(measurements[2].stats.is_err());
+
+        assert_eq!(bench_day(day, "", &Part::ALL, Settings::default()).len(), 1);
+
+        // A panic is that stage's error and the other stages still run.
+        let measurements = bench_day(day, "panic", &Part::ALL, Settings::default());
+        assert!(measurements[1].stats.is_ok());
+        assert_eq!(measurements[2].stats, Err("Panicked: Broken".to_string()));
+    }
+}
@@ -0,0 +1,68 @@
+//! Creates a new day from a template and registers it with its year.
+
+use aoc_common::scaffold::{crate_dir, scaffold};
+use std::error::Error;
+use std::path::PathBuf;
+
+static USAGE: &str = "\
+Usage: new-day <year> <day> [options]
+
+Creates aocYY/src/dayN.rs, registers it in aocYY/src/lib.rs and creates an
+empty aocYY/res/N.
+
+Options:
+    -r, --root <dir>    The workspace directory, defaults to this workspace
+    -h, --help          Print this message";
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut root = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/.."));
+    let mut positional = Vec::new();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-r" | "--root" => {
+                root = args
+                    .next()
+                    .ok_or_else(|| format!("{} needs a value", arg))?
+                    .into()
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => {
+                return Err(format!("Unknown option {}\n\n{}", arg, USAGE).into())
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    if positional.len() != 2 {
+        return Err(USAGE.into());
+    }
+
+    let year: u16 = positional[0].parse()?;
+    let day: u8 = positional[1].parse()?;
+    if !(1..=25).contains(&day) {
+        return Err(format!("Day {} isn't between 1 and 25", day).into());
+    }
+
+    let dir = crate_dir(&root, year);
+    if !dir.is_dir() {
+        return Err(format!("There's no crate for {} at {}", year, dir.display()).into());
+    }
+
+    for path in scaffold(&dir, day)? {
+        println!("Created {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("new-day: {}", e);
+        std::process::exit(1);
+    }
+}
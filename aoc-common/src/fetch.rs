@@ -0,0 +1,495 @@
+//! Downloads puzzle inputs into each year's `res` directory.
+//!
+//! The session token comes from a config file of `key = value` lines, read
+//! from `$AOC_CONFIG` or `~/.config/aoc/config`:
+//!
+//! ```text
+//! # The session cookie of a logged in browser.
+//! session = 53616c7465645f5f...
+//! # Optional, for testing against something other than the real site.
+//! base_url = https://adventofcode.com
+//! # Optional, the minimum number of seconds between requests.
+//! interval = 5
+//! ```
+//!
+//! Inputs that are already on disk aren't downloaded again, and the time of
+//! the last request is kept next to the config so separate runs are limited
+//! too.
+
+use crate::http::{self, Response};
+use crate::runner::Selection;
+use crate::solution::Registry;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static USAGE: &str = "\
+Usage: {name} [options]
+
+Options:
+    -y, --year <year>     The year to fetch, the most recent registered year by
+                          default
+    -d, --day <days>      Days to fetch, e.g. 7, 1-9 or 1,3,5-7
+    -f, --force           Download inputs even if they're already on disk
+    -c, --config <path>   The config file, $AOC_CONFIG or ~/.config/aoc/config
+                          by default
+    -h, --help            Print this message
+
+Inputs are written to res/<day> in the year's crate, rebuild to embed them.";
+
+/// The environment variable naming the config file.
+pub const CONFIG_VAR: &str = "AOC_CONFIG";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = concat!("aoc-common/", env!("CARGO_PKG_VERSION"));
+
+fn usage(name: &str) -> String {
+    USAGE.replace("{name}", name)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    /// The minimum time between requests.
+    pub interval: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Config {
+    /// `$AOC_CONFIG`, otherwise `~/.config/aoc/config`.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(CONFIG_VAR) {
+            return Some(path.into());
+        }
+
+        std::env::var_os("HOME").map(|home| Path::new(&home).join(".config/aoc/config"))
+    }
+
+    /// Reads a config file, a missing file has the default settings.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(s) => s
+                .parse()
+                .map_err(|e| format!("{}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e).into()),
+        }
+    }
+}
+
+impl FromStr for Config {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |e: &dyn std::fmt::Display| format!("line {}: {}: {:?}", i + 1, e, line);
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(&"Expected key = value"))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "session" => config.session = Some(value.to_string()),
+                "base_url" => config.base_url = value.trim_end_matches('/').to_string(),
+                "interval" => {
+                    let seconds = value.parse().map_err(|e| error(&e))?;
+                    config.interval = Duration::try_from_secs_f64(seconds).map_err(|e| error(&e))?
+                }
+                key => return Err(error(&format!("Unknown setting {:?}", key))),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Makes authenticated requests to the puzzle site, waiting between them.
+#[derive(Debug)]
+pub struct Client {
+    config: Config,
+    /// Where the time of the last request is kept between runs.
+    stamp: Option<PathBuf>,
+    last: Option<SystemTime>,
+}
+
+impl Client {
+    pub fn new(config: Config, stamp: Option<PathBuf>) -> Self {
+        Client {
+            config,
+            stamp,
+            last: None,
+        }
+    }
+
+    /// A client using the config at `path`, keeping its request times in
+    /// `<path>.last-request`.
+    pub fn from_config(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut stamp = path.as_os_str().to_owned();
+        stamp.push(".last-request");
+
+        Ok(Client::new(Config::load(path)?, Some(stamp.into())))
+    }
+
+    fn last_request(&self) -> Option<SystemTime> {
+        let stamp = self.stamp.as_ref().and_then(|path| {
+            let nanos = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+            Some(UNIX_EPOCH + Duration::from_nanos(nanos))
+        });
+
+        stamp.max(self.last)
+    }
+
+    /// Sleeps until `interval` has passed since the last request.
+    fn wait(&mut self) -> io::Result<()> {
+        if let Some(last) = self.last_request() {
+            let elapsed = SystemTime::now().duration_since(last).unwrap_or_default();
+
+            if let Some(remaining) = self.config.interval.checked_sub(elapsed) {
+                thread::sleep(remaining);
+            }
+        }
+
+        let now = SystemTime::now();
+        self.last = Some(now);
+
+        if let Some(path) = &self.stamp {
+            let nanos = now
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, nanos.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    fn headers(&self) -> Result<Vec<(&'static str, String)>, Box<dyn Error>> {
+        let session = self
+            .config
+            .session
+            .as_ref()
+            .ok_or("There's no session in the config")?;
+
+        Ok(vec![
+            ("Cookie", format!("session={}", session)),
+            ("User-Agent", USER_AGENT.to_string()),
+        ])
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url, path)
+    }
+
+    /// Gets `path`, relative to the base URL.
+    pub fn get(&mut self, path: &str) -> Result<Response, Box<dyn Error>> {
+        let headers = self.headers()?;
+        let headers: Vec<_> = headers.iter().map(|(n, v)| (*n, v.as_str())).collect();
+
+        self.wait()?;
+        http::get(&self.url(path), &headers)
+    }
+
+    /// Posts a form to `path`, relative to the base URL.
+    pub fn post(&mut self, path: &str, body: &str) -> Result<Response, Box<dyn Error>> {
+        let headers = self.headers()?;
+        let headers: Vec<_> = headers.iter().map(|(n, v)| (*n, v.as_str())).collect();
+
+        self.wait()?;
+        http::post(&self.url(path), &headers, body)
+    }
+}
+
+/// Where a day's input lives in its year's crate.
+pub fn input_path(crate_dir: &Path, day: u8) -> PathBuf {
+    crate_dir.join("res").join(day.to_string())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fetched {
+    /// The input was already on disk.
+    Cached,
+    Downloaded,
+}
+
+/// Downloads a day's input to `path` unless it's already there. Empty files,
+/// like the ones `new-day` creates, don't count.
+pub fn fetch_input(
+    client: &mut Client,
+    year: u16,
+    day: u8,
+    path: &Path,
+    force: bool,
+) -> Result<Fetched, Box<dyn Error>> {
+    if !force && fs::metadata(path).is_ok_and(|m| m.len() > 0) {
+        return Ok(Fetched::Cached);
+    }
+
+    let response = client.get(&format!("/{}/day/{}/input", year, day))?;
+    if !response.is_success() {
+        return Err(format!(
+            "{} day {}: The server responded with {}: {}",
+            year,
+            day,
+            response.status,
+            response.body.trim()
+        )
+        .into());
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, response.body)?;
+
+    Ok(Fetched::Downloaded)
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub year: Option<u16>,
+    pub days: Option<Selection<u8>>,
+    pub force: bool,
+    pub config: Option<PathBuf>,
+}
+
+impl Options {
+    /// Parses the arguments following the subcommand. `name` is only used in
+    /// the usage message.
+    pub fn parse<I>(name: &str, args: I) -> Result<Options, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+            match arg.as_str() {
+                "-y" | "--year" => options.year = Some(value()?.parse()?),
+                "-d" | "--day" => options.days = Some(value()?.parse()?),
+                "-f" | "--force" => options.force = true,
+                "-c" | "--config" => options.config = Some(value()?.into()),
+                "-h" | "--help" => {
+                    println!("{}", usage(name));
+                    std::process::exit(0);
+                }
+                _ => return Err(format!("Unexpected argument {}\n\n{}", arg, usage(name)).into()),
+            }
+        }
+
+        if options.days.is_none() {
+            return Err(usage(name).into());
+        }
+
+        Ok(options)
+    }
+}
+
+pub fn run(options: Options, registry: &Registry) -> Result<(), Box<dyn Error>> {
+    let year = options
+        .year
+        .or_else(|| registry.days().map(|d| d.year).max())
+        .ok_or("There are no registered years")?;
+    let dir = registry
+        .dir(year)
+        .ok_or_else(|| format!("{} doesn't have a directory registered", year))?;
+
+    let config = options
+        .config
+        .or_else(Config::default_path)
+        .ok_or("Couldn't find the config, set $AOC_CONFIG or use --config")?;
+    let mut client = Client::from_config(&config)?;
+
+    let days = options.days.ok_or("No days selected")?;
+
+    for day in (1..=25).filter(|d| days.contains(d)) {
+        let path = input_path(dir, day);
+
+        match fetch_input(&mut client, year, day, &path, options.force)? {
+            Fetched::Cached => eprintln!("{} day {} is already in {}", year, day, path.display()),
+            Fetched::Downloaded => {
+                eprintln!("Fetched {} day {} into {}", year, day, path.display())
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::stand_in;
+    use std::time::Instant;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn client(base_url: &str, interval: Duration) -> Client {
+        let config = Config {
+            session: Some("abc".to_string()),
+            base_url: base_url.to_string(),
+            interval,
+        };
+
+        Client::new(config, None)
+    }
+
+    #[test]
+    fn config() -> Result<(), Box<dyn Error>> {
+        let config: Config = "# Comment\nsession = abc\nbase_url = http://localhost:8080/\n\
+                              interval = 0.5\n"
+            .parse()?;
+        assert_eq!(
+            config,
+            Config {
+                session: Some("abc".to_string()),
+                base_url: "http://localhost:8080".to_string(),
+                interval: Duration::from_millis(500),
+            }
+        );
+
+        assert_eq!("".parse::<Config>()?, Config::default());
+        assert!("session".parse::<Config>().is_err());
+        assert!("colour = red".parse::<Config>().is_err());
+        assert!("interval = soon".parse::<Config>().is_err());
+        for interval in ["-1", "NaN", "inf", "1e300"] {
+            let error = format!("interval = {}", interval)
+                .parse::<Config>()
+                .unwrap_err();
+            assert!(error.starts_with("line 1: "), "{}", error);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn fetches_and_caches() -> Result<(), Box<dyn Error>> {
+        let dir = temp_dir("cache");
+        let path = input_path(&dir, 3);
+        let (url, server) = stand_in::serve(vec![
+            stand_in::response(200, "R8,U5\nU7,R6\n"),
+            stand_in::response(200, "R1\nU1\n"),
+        ]);
+        let mut client = client(&url, Duration::ZERO);
+
+        // An empty placeholder is replaced.
+        fs::create_dir_all(dir.join("res"))?;
+        fs::write(&path, "")?;
+
+        assert_eq!(
+            fetch_input(&mut client, 2019, 3, &path, false)?,
+            Fetched::Downloaded
+        );
+        assert_eq!(fs::read_to_string(&path)?, "R8,U5\nU7,R6\n");
+
+        assert_eq!(
+            fetch_input(&mut client, 2019, 3, &path, false)?,
+            Fetched::Cached
+        );
+        assert_eq!(
+            fetch_input(&mut client, 2019, 3, &path, true)?,
+            Fetched::Downloaded
+        );
+        assert_eq!(fs::read_to_string(&path)?, "R1\nU1\n");
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("GET /2019/day/3/input "));
+        assert!(requests[0].contains("\r\nCookie: session=abc\r\n"));
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn failures() -> Result<(), Box<dyn Error>> {
+        let dir = temp_dir("failures");
+        let path = input_path(&dir, 1);
+        let (url, server) = stand_in::serve(vec![stand_in::response(
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+        )]);
+
+        let error = fetch_input(&mut client(&url, Duration::ZERO), 2019, 1, &path, false)
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("2019 day 1: The server responded with 400: Puzzle inputs"));
+        assert!(!path.exists());
+        server.join().unwrap();
+
+        let mut anonymous = Client::new(Config::default(), None);
+        assert!(fetch_input(&mut anonymous, 2019, 1, &path, false).is_err());
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn rate_limit() -> Result<(), Box<dyn Error>> {
+        let dir = temp_dir("rate");
+        let stamp = dir.join("config.last-request");
+        let config = Config {
+            interval: Duration::from_millis(200),
+            ..Config::default()
+        };
+
+        let start = Instant::now();
+        Client::new(config.clone(), Some(stamp.clone())).wait()?;
+        assert!(start.elapsed() < Duration::from_millis(200));
+
+        // A new client still waits for the request made by the first one.
+        Client::new(config, Some(stamp.clone())).wait()?;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert!(stamp.exists());
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn options() -> Result<(), Box<dyn Error>> {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        let options = Options::parse("aoc fetch", args("-y 2018 -d 1-3 --force -c cfg"))?;
+        assert_eq!(options.year, Some(2018));
+        assert_eq!(options.days, Some("1-3".parse()?));
+        assert!(options.force);
+        assert_eq!(options.config, Some(PathBuf::from("cfg")));
+
+        assert!(Options::parse("aoc fetch", args("-y 2018")).is_err());
+        assert!(Options::parse("aoc fetch", args("-d 1 --nope")).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+//! Grids of cells, either with fixed dimensions or extending in every
+//! direction.
+
+use crate::point::Point2;
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+/// A `width` by `height` grid stored in row-major order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Fails if there aren't exactly `width * height` cells.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Result<Self, String> {
+        if cells.len() != width * height {
+            return Err(format!(
+                "Expected {} cells for {}x{}, got {}",
+                width * height,
+                width,
+                height,
+                cells.len()
+            ));
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point2<usize>) -> bool {
+        p.x < self.width && p.y < self.height
+    }
+
+    pub fn get(&self, p: Point2<usize>) -> Option<&T> {
+        if self.contains(p) {
+            self.cells.get(p.y * self.width + p.x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point2<usize>) -> Option<&mut T> {
+        if self.contains(p) {
+            self.cells.get_mut(p.y * self.width + p.x)
+        } else {
+            None
+        }
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        let width = self.width;
+
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Point2::new(i % width, i / width), cell))
+    }
+
+    /// A grid of the same size with `f` applied to each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The orthogonally adjacent positions inside the grid.
+    pub fn neighbours(&self, p: Point2<usize>) -> impl Iterator<Item = Point2<usize>> + '_ {
+        let p = Point2::new(p.x as i64, p.y as i64);
+
+        IntoIterator::into_iter(p.neighbours())
+            .filter(|n| n.x >= 0 && n.y >= 0)
+            .map(|n| Point2::new(n.x as usize, n.y as usize))
+            .filter(move |n| self.contains(*n))
+    }
+
+    /// Draws each cell as a character, with a newline after each row.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+
+        for row in self.rows() {
+            out.extend(row.iter().map(&f));
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+impl<T> Index<Point2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2<usize>) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", p))
+    }
+}
+
+impl<T> IndexMut<Point2<usize>> for Grid<T> {
+    fn index_mut(&mut self, p: Point2<usize>) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", p))
+    }
+}
+
+/// A grid with no bounds where every cell starts as `default`. Only cells that
+/// have been set are stored.
+#[derive(Clone, Debug)]
+pub struct InfiniteGrid<T> {
+    default: T,
+    cells: HashMap<Point2<i64>, T>,
+}
+
+impl<T> InfiniteGrid<T> {
+    pub fn new(default: T) -> Self {
+        InfiniteGrid {
+            default,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, p: Point2<i64>) -> &T {
+        self.cells.get(&p).unwrap_or(&self.default)
+    }
+
+    pub fn set(&mut self, p: Point2<i64>, value: T) {
+        self.cells.insert(p, value);
+    }
+
+    /// Whether the cell has been set, even if it was set to the default.
+    pub fn is_set(&self, p: Point2<i64>) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// The number of cells that have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The cells that have been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        self.cells.iter().map(|(p, cell)| (*p, cell))
+    }
+}
+
+impl<T: PartialEq> InfiniteGrid<T> {
+    /// The inclusive minimum and maximum corners of the cells that differ from
+    /// the default.
+    pub fn bounds(&self) -> Option<(Point2<i64>, Point2<i64>)> {
+        let mut points = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| **cell != self.default)
+            .map(|(p, _)| *p);
+
+        let first = points.next()?;
+
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point2::new(min.x.min(p.x), min.y.min(p.y)),
+                Point2::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+
+    /// Draws the cells within `bounds` as characters, with a newline after
+    /// each row. An empty grid draws nothing.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        let mut out = String::new();
+
+        if let Some((min, max)) = self.bounds() {
+            for y in min.y..=max.y {
+                out.extend((min.x..=max.x).map(|x| f(self.get(Point2::new(x, y)))));
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid() -> Result<(), String> {
+        let mut grid = Grid::from_cells(3, 2, vec![1, 2, 3, 4, 5, 6])?;
+        assert_eq!(grid[Point2::new(2, 1)], 6);
+        assert_eq!(grid.get(Point2::new(3, 0)), None);
+
+        grid[Point2::new(0, 1)] = 0;
+        assert_eq!(grid.render(|c| (b'0' + *c as u8) as char), "123\n056\n");
+
+        let neighbours: Vec<_> = grid.neighbours(Point2::new(0, 0)).collect();
+        assert_eq!(neighbours, vec![Point2::new(0, 1), Point2::new(1, 0)]);
+
+        assert_eq!(grid.iter().nth(4), Some((Point2::new(1, 1), &5)));
+        assert_eq!(grid.map(|c| c * 2)[Point2::new(2, 1)], 12);
+        assert!(Grid::from_cells(2, 2, vec![1]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn infinite_grid() {
+        let mut grid = InfiniteGrid::new(false);
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.render(|_| '#'), "");
+
+        grid.set(Point2::new(-1, -1), true);
+        grid.set(Point2::new(1, 0), true);
+        grid.set(Point2::new(5, 5), false);
+
+        assert!(*grid.get(Point2::new(1, 0)));
+        assert!(!*grid.get(Point2::new(9, 9)));
+        assert_eq!(grid.len(), 3);
+        assert_eq!(
+            grid.bounds(),
+            Some((Point2::new(-1, -1), Point2::new(1, 0)))
+        );
+        assert_eq!(grid.render(|c| if *c { '#' } else { '.' }), "#..\n..#\n");
+    }
+}
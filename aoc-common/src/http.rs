@@ -0,0 +1,324 @@
+//! Just enough HTTP to talk to the puzzle site. Plain `http://` URLs are
+//! handled over a `TcpStream`, `https://` ones are handed to `curl`.
+
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+pub fn get(url: &str, headers: &[(&str, &str)]) -> Result<Response, Box<dyn Error>> {
+    request("GET", url, headers, None)
+}
+
+/// Posts `body` as `application/x-www-form-urlencoded`.
+pub fn post(url: &str, headers: &[(&str, &str)], body: &str) -> Result<Response, Box<dyn Error>> {
+    request("POST", url, headers, Some(body))
+}
+
+fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, Box<dyn Error>> {
+    if let Some(rest) = url.strip_prefix("http://") {
+        plain(method, rest, headers, body)
+    } else if url.starts_with("https://") {
+        curl(method, url, headers, body)
+    } else {
+        Err(format!("Unsupported URL {:?}", url).into())
+    }
+}
+
+/// Sends an HTTP/1.0 request so the response is never chunked and ends when
+/// the connection closes.
+fn plain(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, Box<dyn Error>> {
+    let (host, path) = match url.find('/') {
+        Some(i) => url.split_at(i),
+        None => (url, "/"),
+    };
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let mut request = format!("{} {} HTTP/1.0\r\nHost: {}\r\n", method, path, host);
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if let Some(body) = body {
+        request.push_str("Content-Type: application/x-www-form-urlencoded\r\n");
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("\r\n");
+    request.push_str(body.unwrap_or_default());
+
+    let mut stream = TcpStream::connect(&address)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    stream.write_all(request.as_bytes())?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    parse_response(&String::from_utf8_lossy(&response))
+}
+
+fn parse_response(response: &str) -> Result<Response, Box<dyn Error>> {
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("The response has no end of headers")?;
+
+    let status_line = head.lines().next().unwrap_or_default();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("Unexpected status line {:?}", status_line))?;
+
+    Ok(Response {
+        status,
+        body: body.to_string(),
+    })
+}
+
+/// Quotes a value for a curl config file.
+fn curl_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The headers and body as a curl config, which is passed on stdin so that
+/// the session cookie isn't visible in the process list.
+fn curl_config(headers: &[(&str, &str)], body: Option<&str>) -> String {
+    let mut config = String::new();
+
+    for (name, value) in headers {
+        config.push_str(&format!(
+            "header = {}\n",
+            curl_quote(&format!("{}: {}", name, value))
+        ));
+    }
+    if let Some(body) = body {
+        config.push_str(&format!("data-raw = {}\n", curl_quote(body)));
+    }
+
+    config
+}
+
+/// Has curl write the status code on a line after the body.
+fn curl(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, Box<dyn Error>> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--request", method])
+        .args(["--max-time", &TIMEOUT.as_secs().to_string()])
+        .args(["--write-out", "\n%{http_code}"])
+        .args(["--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = command
+        .arg(url)
+        .spawn()
+        .map_err(|e| format!("Couldn't run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(curl_config(headers, body).as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "curl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = output
+        .rsplit_once('\n')
+        .ok_or("curl didn't write the status")?;
+
+    Ok(Response {
+        status: status.trim().parse()?,
+        body: body.to_string(),
+    })
+}
+
+/// A stand-in server for tests that answers each connection with the next of
+/// its canned responses and hands back the requests it received.
+#[cfg(test)]
+pub(crate) mod stand_in {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// A response with the given status and body.
+    pub fn response(status: u16, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {} Whatever\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    /// Returns the server's base URL and a handle that yields the requests
+    /// once every response has been sent.
+    pub fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut length = 0;
+
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.strip_prefix("Content-Length: ") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8_lossy(&body));
+                requests.push(request);
+
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+
+            requests
+        });
+
+        (url, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn Error>> {
+        let (url, server) = stand_in::serve(vec![
+            stand_in::response(200, "1\n2\n"),
+            stand_in::response(404, "Not found"),
+        ]);
+
+        let response = get(&format!("{}/2019/day/1/input", url), &[("Cookie", "a=b")])?;
+        assert_eq!(response.status, 200);
+        assert!(response.is_success());
+        assert_eq!(response.body, "1\n2\n");
+
+        let response = post(&format!("{}/answer", url), &[], "level=1&answer=2")?;
+        assert_eq!(response.status, 404);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2019/day/1/input HTTP/1.0\r\n"));
+        assert!(requests[0].contains("\r\nCookie: a=b\r\n"));
+        assert!(requests[1].starts_with("POST /answer HTTP/1.0\r\n"));
+        assert!(requests[1].ends_with("\r\n\r\nlevel=1&answer=2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn responses() -> Result<(), Box<dyn Error>> {
+        let response = parse_response("HTTP/2 302 Found\r\nA: b\r\n\r\nbody")?;
+        assert_eq!(response.status, 302);
+        assert_eq!(response.body, "body");
+
+        assert!(parse_response("HTTP/1.1 200 OK\r\n").is_err());
+        assert!(request("GET", "ftp://example.com", &[], None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn curl_config_quoting() {
+        assert_eq!(
+            curl_config(&[("Cookie", "session=a\"b\\c")], Some("x=1\n")),
+            "header = \"Cookie: session=a\\\"b\\\\c\"\ndata-raw = \"x=1\\n\"\n"
+        );
+    }
+
+    #[test]
+    fn curl_config_on_stdin() -> Result<(), Box<dyn Error>> {
+        if Command::new("curl").arg("--version").output().is_err() {
+            eprintln!("Skipping, curl isn't installed");
+            return Ok(());
+        }
+
+        let (url, server) = stand_in::serve(vec![stand_in::response(200, "Right")]);
+        let response = curl(
+            "POST",
+            &format!("{}/answer", url),
+            &[("Cookie", "session=abc")],
+            Some("level=1&answer=@2"),
+        )?;
+        assert_eq!(
+            response,
+            Response {
+                status: 200,
+                body: "Right".to_string()
+            }
+        );
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /answer HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=1&answer=@2"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+//! Writes grids of pixels as netpbm (PGM and PPM) or PNG images.
+//!
+//! The PNG encoder doesn't compress, the images puzzles produce are small.
+
+use crate::grid::Grid;
+use crate::point::Point2;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A red, green and blue colour.
+pub type Rgb = [u8; 3];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Pgm,
+    Ppm,
+    Png,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Pgm => "pgm",
+            Format::Ppm => "ppm",
+            Format::Png => "png",
+        }
+    }
+
+    /// The format matching a path's extension.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| format!("{} has no extension", path.display()))?
+            .parse()
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pgm" => Ok(Format::Pgm),
+            "ppm" => Ok(Format::Ppm),
+            "png" => Ok(Format::Png),
+            _ => Err(format!(
+                "Unknown image format {:?}, expected pgm, ppm or png",
+                s
+            )),
+        }
+    }
+}
+
+/// Each pixel repeated `factor` times in both directions.
+pub fn scale<T: Clone>(grid: &Grid<T>, factor: usize) -> Grid<T> {
+    let width = grid.width() * factor;
+    let height = grid.height() * factor;
+    let cells = (0..height)
+        .flat_map(|y| (0..width).map(move |x| Point2::new(x / factor, y / factor)))
+        .map(|p| grid[p].clone())
+        .collect();
+
+    Grid::from_cells(width, height, cells).expect("The scaled size matches the cells")
+}
+
+/// The perceived brightness of a colour.
+pub fn grey([r, g, b]: Rgb) -> u8 {
+    ((u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000) as u8
+}
+
+fn netpbm(magic: &str, grid: &Grid<Rgb>, channels: impl Fn(Rgb) -> Vec<u8>) -> Vec<u8> {
+    let mut out = format!("{}\n{} {}\n255\n", magic, grid.width(), grid.height()).into_bytes();
+    for (_, &pixel) in grid.iter() {
+        out.extend(channels(pixel));
+    }
+    out
+}
+
+/// A binary greyscale netpbm image.
+pub fn pgm(grid: &Grid<Rgb>) -> Vec<u8> {
+    netpbm("P5", grid, |pixel| vec![grey(pixel)])
+}
+
+/// A binary colour netpbm image.
+pub fn ppm(grid: &Grid<Rgb>) -> Vec<u8> {
+    netpbm("P6", grid, |pixel| pixel.to_vec())
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for &byte in bytes {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+/// Wraps `data` in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xFFFF).peekable();
+
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let length = block.len() as u16;
+
+        out.push(u8::from(last));
+        out.extend(length.to_le_bytes());
+        out.extend((!length).to_le_bytes());
+        out.extend(block);
+    }
+
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// An 8 bit RGB PNG image.
+pub fn png(grid: &Grid<Rgb>) -> Vec<u8> {
+    let mut header = Vec::with_capacity(13);
+    header.extend((grid.width() as u32).to_be_bytes());
+    header.extend((grid.height() as u32).to_be_bytes());
+    // Bit depth, colour type (RGB), compression, filter and interlacing.
+    header.extend([8, 2, 0, 0, 0]);
+
+    // Each row starts with its filter type, none.
+    let mut rows = Vec::with_capacity((grid.width() * 3 + 1) * grid.height());
+    for row in grid.rows().take(grid.height()) {
+        rows.push(0);
+        rows.extend(row.iter().flatten());
+    }
+
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut out, b"IHDR", &header);
+    chunk(&mut out, b"IDAT", &zlib_stored(&rows));
+    chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+/// Encodes the grid in the given format.
+pub fn encode(grid: &Grid<Rgb>, format: Format) -> Vec<u8> {
+    match format {
+        Format::Pgm => pgm(grid),
+        Format::Ppm => ppm(grid),
+        Format::Png => png(grid),
+    }
+}
+
+/// Writes the grid in the format matching the path's extension.
+pub fn save(grid: &Grid<Rgb>, path: &Path) -> Result<(), Box<dyn Error>> {
+    let format = Format::from_path(path)?;
+    fs::write(path, encode(grid, format)).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgb = [255, 255, 255];
+    const RED: Rgb = [255, 0, 0];
+
+    fn image() -> Grid<Rgb> {
+        Grid::from_cells(2, 1, vec![WHITE, RED]).unwrap()
+    }
+
+    /// Undoes `zlib_stored`, checking the lengths and checksum.
+    fn unstore(zlib: &[u8]) -> Vec<u8> {
+        assert_eq!(&zlib[..2], &[0x78, 0x01]);
+
+        let mut data = Vec::new();
+        let mut i = 2;
+        loop {
+            let last = zlib[i] & 1 == 1;
+            let length = u16::from_le_bytes([zlib[i + 1], zlib[i + 2]]);
+            let inverse = u16::from_le_bytes([zlib[i + 3], zlib[i + 4]]);
+            assert_eq!(length, !inverse);
+
+            data.extend(&zlib[i + 5..i + 5 + length as usize]);
+            i += 5 + length as usize;
+
+            if last {
+                break;
+            }
+        }
+
+        assert_eq!(zlib[i..], adler32(&data).to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn netpbm() {
+        assert_eq!(pgm(&image()), b"P5\n2 1\n255\n\xff\x4c".to_vec());
+        assert_eq!(
+            ppm(&image()),
+            b"P6\n2 1\n255\n\xff\xff\xff\xff\x00\x00".to_vec()
+        );
+    }
+
+    #[test]
+    fn png_chunks() {
+        let png = png(&scale(&image(), 2));
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let mut chunks = Vec::new();
+        let mut i = 8;
+        while i < png.len() {
+            let length = u32::from_be_bytes([png[i], png[i + 1], png[i + 2], png[i + 3]]) as usize;
+            let body = &png[i + 4..i + 8 + length];
+            let crc = &png[i + 8 + length..i + 12 + length];
+            assert_eq!(crc, crc32(body).to_be_bytes());
+
+            chunks.push((body[..4].to_vec(), body[4..].to_vec()));
+            i += 12 + length;
+        }
+
+        let kinds: Vec<&[u8]> = chunks.iter().map(|(k, _)| &k[..]).collect();
+        assert_eq!(kinds, vec![&b"IHDR"[..], b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 4, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+
+        let row = [0, 255, 255, 255, 255, 255, 255, 255, 0, 0, 255, 0, 0];
+        assert_eq!(unstore(&chunks[1].1), [row, row].concat());
+    }
+
+    #[test]
+    fn large_data() {
+        let data: Vec<u8> = (0..200_000).map(|i| (i % 251) as u8).collect();
+        assert_eq!(unstore(&zlib_stored(&data)), data);
+        assert_eq!(unstore(&zlib_stored(&[])), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(Format::from_path(Path::new("a/b.png")), Ok(Format::Png));
+        assert!(Format::from_path(Path::new("a/b")).is_err());
+        assert!("gif".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,25 @@
+//! Shared between the year crates.
+
+pub mod answers;
+pub mod bench;
+pub mod fetch;
+pub mod grid;
+pub mod http;
+pub mod image;
+pub mod ocr;
+pub mod parse;
+pub mod point;
+pub mod random;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod segments;
+pub mod solution;
+pub mod submit;
+pub mod watch;
+
+pub use answers::Answers;
+pub use grid::{Grid, InfiniteGrid};
+pub use parse::ParseError;
+pub use point::{Direction, Point2, Point3};
+pub use solution::{Day, Example, Part, Registry, Solution};
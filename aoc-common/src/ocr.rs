@@ -0,0 +1,274 @@
+//! Reads the block letters some puzzles draw as their answer.
+//!
+//! Two fonts are known: letters 6 pixels high, mostly 4 wide, and letters 10
+//! pixels high, mostly 6 wide. Letters are separated by at least one blank
+//! column, so they're found by splitting the image on blank columns and
+//! matching each piece against the font for the image's height.
+
+use crate::grid::Grid;
+use crate::point::Point2;
+
+/// The 6 pixel high font.
+static SMALL: &[(char, &str)] = &[
+    ('A', ".##.|#..#|#..#|####|#..#|#..#"),
+    ('B', "###.|#..#|###.|#..#|#..#|###."),
+    ('C', ".##.|#..#|#...|#...|#..#|.##."),
+    ('E', "####|#...|###.|#...|#...|####"),
+    ('F', "####|#...|###.|#...|#...|#..."),
+    ('G', ".##.|#..#|#...|#.##|#..#|.###"),
+    ('H', "#..#|#..#|####|#..#|#..#|#..#"),
+    ('I', "###|.#.|.#.|.#.|.#.|###"),
+    ('J', "..##|...#|...#|...#|#..#|.##."),
+    ('K', "#..#|#.#.|##..|#.#.|#.#.|#..#"),
+    ('L', "#...|#...|#...|#...|#...|####"),
+    ('O', ".##.|#..#|#..#|#..#|#..#|.##."),
+    ('P', "###.|#..#|#..#|###.|#...|#..."),
+    ('R', "###.|#..#|#..#|###.|#.#.|#..#"),
+    ('S', ".###|#...|#...|.##.|...#|###."),
+    ('U', "#..#|#..#|#..#|#..#|#..#|.##."),
+    ('Y', "#...#|#...#|.#.#.|..#..|..#..|..#.."),
+    ('Z', "####|...#|..#.|.#..|#...|####"),
+];
+
+/// The 10 pixel high font.
+static LARGE: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..|.#..#.|#....#|#....#|#....#|######|#....#|#....#|#....#|#....#",
+    ),
+    (
+        'B',
+        "#####.|#....#|#....#|#....#|#####.|#....#|#....#|#....#|#....#|#####.",
+    ),
+    (
+        'C',
+        ".####.|#....#|#.....|#.....|#.....|#.....|#.....|#.....|#....#|.####.",
+    ),
+    (
+        'E',
+        "######|#.....|#.....|#.....|#####.|#.....|#.....|#.....|#.....|######",
+    ),
+    (
+        'F',
+        "######|#.....|#.....|#.....|#####.|#.....|#.....|#.....|#.....|#.....",
+    ),
+    (
+        'G',
+        ".####.|#....#|#.....|#.....|#.....|#..###|#....#|#....#|#...##|.###.#",
+    ),
+    (
+        'H',
+        "#....#|#....#|#....#|#....#|######|#....#|#....#|#....#|#....#|#....#",
+    ),
+    (
+        'J',
+        "...###|....#.|....#.|....#.|....#.|....#.|....#.|#...#.|#...#.|.###..",
+    ),
+    (
+        'K',
+        "#....#|#...#.|#..#..|#.#...|##....|##....|#.#...|#..#..|#...#.|#....#",
+    ),
+    (
+        'L',
+        "#.....|#.....|#.....|#.....|#.....|#.....|#.....|#.....|#.....|######",
+    ),
+    (
+        'N',
+        "#....#|##...#|##...#|#.#..#|#.#..#|#..#.#|#..#.#|#...##|#...##|#....#",
+    ),
+    (
+        'P',
+        "#####.|#....#|#....#|#....#|#####.|#.....|#.....|#.....|#.....|#.....",
+    ),
+    (
+        'R',
+        "#####.|#....#|#....#|#....#|#####.|#..#..|#...#.|#...#.|#....#|#....#",
+    ),
+    (
+        'X',
+        "#....#|#....#|.#..#.|.#..#.|..##..|..##..|.#..#.|.#..#.|#....#|#....#",
+    ),
+    (
+        'Z',
+        "######|.....#|.....#|....#.|...#..|..#...|.#....|#.....|#.....|######",
+    ),
+];
+
+/// Draws a piece of an image in the fonts' notation, rows separated by `|`.
+fn pattern(grid: &Grid<bool>, columns: &[usize]) -> String {
+    (0..grid.height())
+        .map(|y| {
+            columns
+                .iter()
+                .map(|&x| if grid[Point2::new(x, y)] { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// The image without blank rows and columns around it.
+fn trim(grid: &Grid<bool>) -> Grid<bool> {
+    let lit: Vec<Point2<usize>> = grid.iter().filter(|(_, &c)| c).map(|(p, _)| p).collect();
+
+    let (min_x, max_x) = (lit.iter().map(|p| p.x).min(), lit.iter().map(|p| p.x).max());
+    let (min_y, max_y) = (lit.iter().map(|p| p.y).min(), lit.iter().map(|p| p.y).max());
+
+    match (min_x, max_x, min_y, max_y) {
+        (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) => {
+            let cells = (min_y..=max_y)
+                .flat_map(|y| (min_x..=max_x).map(move |x| grid[Point2::new(x, y)]))
+                .collect();
+            Grid::from_cells(max_x - min_x + 1, max_y - min_y + 1, cells)
+                .expect("The trimmed size matches the cells")
+        }
+        _ => Grid::new(0, 0, false),
+    }
+}
+
+/// Reads the letters in the image. Fails if the image isn't the height of
+/// either font or a letter isn't recognised.
+pub fn recognise(grid: &Grid<bool>) -> Result<String, String> {
+    let grid = trim(grid);
+
+    let font = match grid.height() {
+        0 => return Ok(String::new()),
+        6 => SMALL,
+        10 => LARGE,
+        height => {
+            return Err(format!(
+                "Expected letters 6 or 10 pixels high, got {}",
+                height
+            ))
+        }
+    };
+
+    let blank = |x: usize| (0..grid.height()).all(|y| !grid[Point2::new(x, y)]);
+    let mut text = String::new();
+    let mut columns = Vec::new();
+
+    for x in 0..=grid.width() {
+        if x < grid.width() && !blank(x) {
+            columns.push(x);
+            continue;
+        }
+
+        if columns.is_empty() {
+            continue;
+        }
+
+        let glyph = pattern(&grid, &columns);
+        let letter = font
+            .iter()
+            .find(|(_, p)| *p == glyph)
+            .map(|(c, _)| *c)
+            .ok_or_else(|| {
+                format!(
+                    "Unrecognised letter in columns {}-{}:\n{}",
+                    columns[0],
+                    columns[columns.len() - 1],
+                    glyph.replace('|', "\n")
+                )
+            })?;
+
+        text.push(letter);
+        columns.clear();
+    }
+
+    Ok(text)
+}
+
+/// Reads the letters in ASCII art where `#` is lit and anything else isn't.
+pub fn recognise_art(art: &str) -> Result<String, String> {
+    let width = art.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    let height = art.lines().count();
+    let cells = art
+        .lines()
+        .flat_map(|line| {
+            let length = line.chars().count();
+            line.chars()
+                .map(|c| c == '#')
+                .chain(std::iter::repeat_n(false, width - length))
+        })
+        .collect();
+
+    recognise(&Grid::from_cells(width, height, cells)?)
+}
+
+/// Draws text in the 6 pixel high font with a blank column between letters,
+/// the inverse of `recognise`.
+pub fn render(text: &str) -> Result<Grid<bool>, String> {
+    let glyphs: Vec<Vec<&str>> = text
+        .chars()
+        .map(|c| {
+            SMALL
+                .iter()
+                .find(|(letter, _)| *letter == c)
+                .map(|(_, p)| p.split('|').collect())
+                .ok_or_else(|| format!("There's no {:?} in the font", c))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let width = glyphs
+        .iter()
+        .map(|g| g[0].len() + 1)
+        .sum::<usize>()
+        .saturating_sub(1);
+    let mut grid = Grid::new(width, 6, false);
+    let mut left = 0;
+
+    for glyph in glyphs {
+        for (y, row) in glyph.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid[Point2::new(left + x, y)] = c == '#';
+            }
+        }
+        left += glyph[0].len() + 1;
+    }
+
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_font() -> Result<(), String> {
+        let art = "\
+#    ####  ##    ## #   #
+#    #    #  #    # #   #
+#    ###  #       #  # #
+#    #    # ##    #   #
+#    #    #  # #  #   #
+#### ####  ###  ##    #  ";
+        assert_eq!(recognise_art(art)?, "LEGJY");
+
+        let alphabet: String = SMALL.iter().map(|(c, _)| c).collect();
+        assert_eq!(recognise(&render(&alphabet)?)?, alphabet);
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_font() -> Result<(), String> {
+        for (letter, glyph) in LARGE {
+            // Surrounded by blank space, which is ignored.
+            let art = format!("\n {}\n", glyph.replace('|', "\n "));
+            assert_eq!(recognise_art(&art)?, letter.to_string());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn failures() {
+        assert_eq!(recognise_art(""), Ok(String::new()));
+        assert!(recognise_art("#\n#\n#").is_err());
+
+        let error = recognise_art("####\n#..#\n#..#\n#..#\n#..#\n####").unwrap_err();
+        assert!(error.starts_with("Unrecognised letter in columns 0-3:\n####\n#..#\n"));
+
+        assert!(render("a").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Helpers for common puzzle input layouts.
+//!
+//! Parsers report problems as a `ParseError`, which says where in the input
+//! the problem is and what the offending text was.
+
+use crate::grid::Grid;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Something in the input that couldn't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// The line the problem is on, counting from 1.
+    pub line: usize,
+    /// The text that couldn't be parsed, either the line or part of it.
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, text: &str, message: impl Display) -> Self {
+        ParseError {
+            line,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}: {:?}", self.line, self.message, self.text)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses a number, ignoring surrounding whitespace, with an error that
+/// names the text.
+pub fn number<T>(s: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.trim()
+        .parse()
+        .map_err(|e| format!("Couldn't parse {:?}: {}", s, e))
+}
+
+/// Parses each line with `f`, ignoring blank lines. Errors are reported with
+/// the line they came from.
+pub fn lines<T, E: Display>(
+    input: &str,
+    mut f: impl FnMut(&str) -> Result<T, E>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| f(line).map_err(|e| ParseError::new(i + 1, line, e)))
+        .collect()
+}
+
+/// Parses a map with one character per cell, e.g. `#` and `.`, ignoring blank
+/// lines. `f` returns `None` for characters that aren't allowed.
+pub fn char_map<T>(
+    input: &str,
+    mut f: impl FnMut(char) -> Option<T>,
+) -> Result<Grid<T>, Box<dyn Error>> {
+    let mut cells = Vec::new();
+    let mut width = None;
+    let mut height = 0;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let length = line.chars().count();
+        if *width.get_or_insert(length) != length {
+            let message = format!(
+                "Expected {} characters, got {}",
+                width.unwrap_or_default(),
+                length
+            );
+            return Err(ParseError::new(i + 1, line, message).into());
+        }
+
+        for (column, c) in line.chars().enumerate() {
+            let cell = f(c).ok_or_else(|| {
+                let message = format!("Unexpected {:?} in column {}", c, column + 1);
+                ParseError::new(i + 1, line, message)
+            })?;
+            cells.push(cell);
+        }
+
+        height += 1;
+    }
+
+    Ok(Grid::from_cells(width.unwrap_or_default(), height, cells)?)
+}
+
+/// Parses integers separated by commas and/or whitespace. Errors have the
+/// number that couldn't be parsed as their text.
+pub fn integers<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let mut values = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        for s in line.split(|c: char| c == ',' || c.is_whitespace()) {
+            if !s.is_empty() {
+                values.push(s.parse().map_err(|e| ParseError::new(i + 1, s, e))?);
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point2;
+
+    #[test]
+    fn maps() -> Result<(), Box<dyn Error>> {
+        let walls = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+
+        let grid = char_map("\n.#.\n##.\n", walls)?;
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert!(grid[Point2::new(1, 0)]);
+        assert!(!grid[Point2::new(2, 1)]);
+
+        assert!(char_map(".#\n#", walls).is_err());
+        assert_eq!(
+            char_map(".#\n.x", walls).unwrap_err().to_string(),
+            "line 2: Unexpected 'x' in column 2: \".x\""
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn integer_lists() -> Result<(), Box<dyn Error>> {
+        assert_eq!(integers::<i32>("+1, -2\n3")?, vec![1, -2, 3]);
+        assert_eq!(integers::<u8>("")?, vec![]);
+        assert_eq!(
+            integers::<u8>("1,2\n3,x,4"),
+            Err(ParseError::new(2, "x", "invalid digit found in string"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn line_errors() {
+        assert_eq!(number::<u16>(" 12 "), Ok(12));
+        assert_eq!(
+            number::<u8>("300"),
+            Err("Couldn't parse \"300\": number too large to fit in target type".to_string())
+        );
+
+        let parsed = lines("1\n\n2\n", |l| l.parse::<u8>());
+        assert_eq!(parsed, Ok(vec![1, 2]));
+
+        let error = lines("1\n\nthree", |l| l.parse::<u8>()).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.text, "three");
+        assert_eq!(
+            error.to_string(),
+            "line 3: invalid digit found in string: \"three\""
+        );
+    }
+}
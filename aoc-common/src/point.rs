@@ -0,0 +1,299 @@
+//! Points and directions. Puzzles draw y increasing downwards, so `Up` is
+//! towards negative y.
+
+use std::convert::TryFrom;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// `|a - b|`, without needing a signed type.
+fn distance<T: Copy + PartialOrd + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>> Point2<T> {
+    pub fn manhattan_distance(self, other: Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y)
+    }
+}
+
+impl<T: Copy + Default + PartialOrd + Add<Output = T> + Sub<Output = T>> Point2<T> {
+    /// The manhattan distance from the origin.
+    pub fn manhattan(self) -> T {
+        self.manhattan_distance(Self::default())
+    }
+}
+
+impl<T: Copy + From<i8> + Add<Output = T>> Point2<T> {
+    /// The adjacent point in `dir`.
+    pub fn step(self, dir: Direction) -> Self {
+        self + dir.delta()
+    }
+
+    /// The four orthogonally adjacent points, in `Direction::ALL` order.
+    pub fn neighbours(self) -> [Self; 4] {
+        let [up, down, left, right] = Direction::ALL;
+        [
+            self.step(up),
+            self.step(down),
+            self.step(left),
+            self.step(right),
+        ]
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, scale: T) -> Self {
+        Point2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>> Point3<T> {
+    pub fn manhattan_distance(self, other: Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y) + distance(self.z, other.z)
+    }
+}
+
+impl<T: Copy + Default + PartialOrd + Add<Output = T> + Sub<Output = T>> Point3<T> {
+    /// The manhattan distance from the origin.
+    pub fn manhattan(self) -> T {
+        self.manhattan_distance(Self::default())
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, scale: T) -> Self {
+        Point3::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Point3<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3::new(x, y, z)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        self.turn_left().turn_left()
+    }
+
+    /// The offset of a single step.
+    pub fn delta<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        };
+
+        Point2::new(T::from(x), T::from(y))
+    }
+}
+
+/// Accepts `UDLR`, compass points `NSWE` and arrows `^v<>`.
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | 'N' | '^' => Ok(Direction::Up),
+            'D' | 'S' | 'v' => Ok(Direction::Down),
+            'L' | 'W' | '<' => Ok(Direction::Left),
+            'R' | 'E' | '>' => Ok(Direction::Right),
+            _ => Err(format!("Unsupported direction: {:?}", c)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Point2::new(3i16, -4);
+        let b = Point2::new(1, 2);
+
+        assert_eq!(a + b, Point2::new(4, -2));
+        assert_eq!(a - b, Point2::new(2, -6));
+        assert_eq!(b * 3, Point2::new(3, 6));
+        assert_eq!(-b, Point2::new(-1, -2));
+        assert_eq!(a.manhattan(), 7);
+
+        let c = Point2::new(5u16, 1);
+        assert_eq!(c.manhattan_distance(Point2::new(2, 4)), 6);
+
+        let d = Point3::new(1i64, -2, 3);
+        assert_eq!(d + d, Point3::new(2, -4, 6));
+        assert_eq!(d.manhattan(), 6);
+    }
+
+    #[test]
+    fn directions() {
+        let origin = Point2::new(0i64, 0);
+        assert_eq!(origin.step(Direction::Up), Point2::new(0, -1));
+        assert_eq!(
+            origin.neighbours(),
+            [
+                Point2::new(0, -1),
+                Point2::new(0, 1),
+                Point2::new(-1, 0),
+                Point2::new(1, 0)
+            ]
+        );
+
+        let mut dir = Direction::Up;
+        for _ in 0..4 {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            dir = dir.turn_right();
+        }
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+
+        assert_eq!(Direction::try_from('^'), Ok(Direction::Up));
+        assert_eq!(Direction::try_from('E'), Ok(Direction::Right));
+        assert!(Direction::try_from('x').is_err());
+    }
+}
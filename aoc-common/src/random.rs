@@ -0,0 +1,59 @@
+//! A small pseudo-random generator for when the numbers only need to look
+//! random and come out the same for the same seed.
+
+/// A xorshift generator.
+#[derive(Clone, Debug)]
+pub struct Xorshift {
+    state: u64,
+}
+
+impl Xorshift {
+    /// A seed of 0 is swapped for a fixed one, since xorshift gets stuck at 0.
+    pub fn new(seed: u64) -> Self {
+        Xorshift {
+            state: if seed == 0 {
+                0x2545_f491_4f6c_dd1d
+            } else {
+                seed
+            },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A number from 0 up to but not including `n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+impl Default for Xorshift {
+    fn default() -> Self {
+        Xorshift::new(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds() {
+        let numbers = |seed| {
+            let mut random = Xorshift::new(seed);
+            (0..5).map(|_| random.next_u64()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        assert!(numbers(0).iter().all(|&n| n != 0));
+
+        let mut random = Xorshift::default();
+        assert!((0..100).all(|_| random.below(3) < 3));
+    }
+}
@@ -0,0 +1,171 @@
+//! Machine-readable output of the runner's results.
+
+use crate::runner::Outcome;
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "Unknown format {:?}, expected table, json or csv",
+                s
+            )),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+fn json_option(s: Option<&str>) -> String {
+    s.map_or_else(|| "null".to_string(), json_string)
+}
+
+/// An array with an object per outcome. The answer is `null` when the part
+/// failed and the error `null` when it didn't.
+pub fn json(outcomes: &[Outcome]) -> String {
+    let mut out = String::from("[");
+
+    for (i, o) in outcomes.iter().enumerate() {
+        let _ = write!(
+            out,
+            "{}\n  {{\"year\": {}, \"day\": {}, \"part\": {}, \"answer\": {}, \"error\": {}, \
+             \"elapsed_ns\": {}, \"status\": \"{}\"}}",
+            if i == 0 { "" } else { "," },
+            o.year,
+            o.day,
+            o.part,
+            json_option(o.answer.as_ref().ok().map(String::as_str)),
+            json_option(o.answer.as_ref().err().map(String::as_str)),
+            o.elapsed.as_nanos(),
+            o.status()
+        );
+    }
+
+    out.push_str(if outcomes.is_empty() { "]\n" } else { "\n]\n" });
+    out
+}
+
+/// Quotes fields containing separators, quotes or line breaks.
+fn csv_field(s: &str) -> String {
+    if s.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// A header row followed by a row per outcome.
+pub fn csv(outcomes: &[Outcome]) -> String {
+    let mut out = String::from("year,day,part,answer,error,elapsed_ns,status\n");
+
+    for o in outcomes {
+        let (answer, error) = match &o.answer {
+            Ok(answer) => (csv_field(answer), String::new()),
+            Err(e) => (String::new(), csv_field(e)),
+        };
+
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            o.year,
+            o.day,
+            o.part,
+            answer,
+            error,
+            o.elapsed.as_nanos(),
+            o.status()
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Part;
+    use std::time::Duration;
+
+    fn outcomes() -> Vec<Outcome> {
+        vec![
+            Outcome {
+                year: 2019,
+                day: 8,
+                part: Part::Two,
+                answer: Ok("# \"a\",\nb".to_string()),
+                elapsed: Duration::from_micros(15),
+                expected: None,
+            },
+            Outcome {
+                year: 2019,
+                day: 10,
+                part: Part::One,
+                answer: Err("Not solved".to_string()),
+                elapsed: Duration::from_nanos(7),
+                expected: Some("284".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn json_output() {
+        assert_eq!(
+            json(&outcomes()),
+            "[\n  {\"year\": 2019, \"day\": 8, \"part\": 2, \"answer\": \"# \\\"a\\\",\\nb\", \
+             \"error\": null, \"elapsed_ns\": 15000, \"status\": \"unknown\"},\n  \
+             {\"year\": 2019, \"day\": 10, \"part\": 1, \"answer\": null, \
+             \"error\": \"Not solved\", \"elapsed_ns\": 7, \"status\": \"error\"}\n]\n"
+        );
+        assert_eq!(json(&[]), "[]\n");
+    }
+
+    #[test]
+    fn csv_output() {
+        assert_eq!(
+            csv(&outcomes()),
+            "year,day,part,answer,error,elapsed_ns,status\n\
+             2019,8,2,\"# \"\"a\"\",\nb\",,15000,unknown\n\
+             2019,10,1,,Not solved,7,error\n"
+        );
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,1083 @@
+//! The command line runner shared by each year's binary.
+
+use crate::answers::Answers;
+use crate::bench::{self, Settings};
+use crate::fetch;
+use crate::report::{self, Format};
+use crate::solution::{Day, Part, Registry};
+use crate::submit;
+use crate::watch;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+static USAGE: &str = "\
+Usage: {name} [options]
+       {name} fetch [options]    Download puzzle inputs, see fetch --help
+       {name} submit [options]   Submit an answer, see submit --help
+
+Options:
+    -y, --year <years>    Years to run, e.g. 2019 or 2018,2019
+    -d, --day <days>      Days to run, e.g. 7, 1-9 or 1,3,5-7
+    -p, --part <part>     Only run part 1 or 2
+    -a, --all             Run every registered day of every year
+    -i, --input <path>    Read the input from a file, or stdin if it's -
+    -I, --inputs <dir>    Read inputs from <dir>/<year>/<day> when present
+    -c, --check           Compare answers with each year's answers file and
+                          check the examples, runs every day unless --year or
+                          --day is given
+    -r, --record          Add answers missing from the answers files
+    -f, --format <fmt>    Print results as a table, json or csv
+    -j, --jobs <n>        Run days on n threads, or one per CPU if n is 0, and
+                          print how long each day took
+    -b, --bench           Time parsing and each part instead of printing answers
+    -w, --watch           Rebuild and rerun the selected day whenever its source
+                          or input changes, comparing with the previous run
+        --iterations <n>  Timed runs of each stage when benchmarking, 10 by default
+        --warmup <n>      Untimed runs before timing each stage, 3 by default
+    -h, --help            Print this message
+
+Without --year the most recent registered year is used. The inputs directory
+defaults to $AOC_INPUTS, days without a file there use their embedded input.
+The answers files only apply to the embedded inputs.";
+
+/// The environment variable naming the default inputs directory.
+pub const INPUTS_VAR: &str = "AOC_INPUTS";
+
+fn usage(name: &str) -> String {
+    USAGE.replace("{name}", name)
+}
+
+/// A comma separated list of numbers and inclusive ranges, e.g. `1,3,5-7`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Selection<T>(Vec<RangeInclusive<T>>);
+
+impl<T: PartialOrd> Selection<T> {
+    pub fn contains(&self, value: &T) -> bool {
+        self.0.iter().any(|range| range.contains(value))
+    }
+}
+
+impl<T> FromStr for Selection<T>
+where
+    T: FromStr + PartialOrd,
+    T::Err: Error + 'static,
+{
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = Vec::new();
+
+        for item in s.split(',') {
+            let mut bounds = item.splitn(2, '-');
+            let start: T = bounds.next().unwrap_or_default().trim().parse()?;
+            let end: T = match bounds.next() {
+                Some(end) => end.trim().parse()?,
+                None => item.trim().parse()?,
+            };
+
+            if start > end {
+                return Err(format!("The range {:?} is backwards", item).into());
+            }
+
+            ranges.push(start..=end);
+        }
+
+        Ok(Selection(ranges))
+    }
+}
+
+/// Where each day's puzzle input is read from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Source {
+    /// The input compiled into the binary.
+    #[default]
+    Embedded,
+    /// `<dir>/<year>/<day>`, falling back to the embedded input.
+    Dir(PathBuf),
+    /// A single file, only usable when one day is selected.
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    pub fn load(&self, day: &Day) -> io::Result<Cow<'static, str>> {
+        match self {
+            Source::Embedded => Ok(Cow::Borrowed(day.input)),
+            Source::Dir(dir) => {
+                let path = dir.join(day.year.to_string()).join(day.day.to_string());
+                match fs::read_to_string(&path) {
+                    Ok(input) => Ok(Cow::Owned(input)),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Cow::Borrowed(day.input)),
+                    Err(e) => Err(io::Error::new(
+                        e.kind(),
+                        format!("{}: {}", path.display(), e),
+                    )),
+                }
+            }
+            Source::File(path) => fs::read_to_string(path)
+                .map(Cow::Owned)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e))),
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(Cow::Owned(input))
+            }
+        }
+    }
+
+    /// Whether the source can only provide a single day's input.
+    fn is_single(&self) -> bool {
+        matches!(self, Source::File(_) | Source::Stdin)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub years: Option<Selection<u16>>,
+    pub days: Option<Selection<u8>>,
+    pub part: Option<Part>,
+    pub all: bool,
+    pub source: Source,
+    pub check: bool,
+    pub record: bool,
+    pub bench: bool,
+    pub bench_settings: Settings,
+    pub format: Format,
+    /// `None` runs days one at a time without a timing summary.
+    pub jobs: Option<usize>,
+    pub watch: bool,
+}
+
+impl Options {
+    /// Parses the arguments following the program name. `name` is only used
+    /// in the usage message.
+    pub fn parse<I>(name: &str, args: I) -> Result<Options, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+            match arg.as_str() {
+                "-y" | "--year" => options.years = Some(value()?.parse()?),
+                "-d" | "--day" => options.days = Some(value()?.parse()?),
+                "-p" | "--part" => options.part = Some(value()?.parse()?),
+                "-a" | "--all" => options.all = true,
+                "-i" | "--input" => {
+                    options.source = match value()?.as_str() {
+                        "-" => Source::Stdin,
+                        path => Source::File(path.into()),
+                    }
+                }
+                "-I" | "--inputs" => options.source = Source::Dir(value()?.into()),
+                "-c" | "--check" => options.check = true,
+                "-r" | "--record" => options.record = true,
+                "-f" | "--format" => options.format = value()?.parse()?,
+                "-j" | "--jobs" => options.jobs = Some(value()?.parse()?),
+                "-b" | "--bench" => options.bench = true,
+                "-w" | "--watch" => options.watch = true,
+                "--iterations" => options.bench_settings.iterations = value()?.parse()?,
+                "--warmup" => options.bench_settings.warmup = value()?.parse()?,
+                "-h" | "--help" => {
+                    println!("{}", usage(name));
+                    std::process::exit(0);
+                }
+                _ => return Err(format!("Unexpected argument {}\n\n{}", arg, usage(name)).into()),
+            }
+        }
+
+        if options.years.is_none() && options.days.is_none() {
+            if options.check {
+                options.all = true;
+            } else if !options.all {
+                return Err(usage(name).into());
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+
+    /// The registered days matching the options, in (year, day) order.
+    pub fn select<'a>(&self, registry: &'a Registry) -> Vec<&'a Day> {
+        let latest = registry.days().map(|d| d.year).max();
+
+        registry
+            .days()
+            .filter(|d| match &self.years {
+                Some(years) => years.contains(&d.year),
+                None => self.all || Some(d.year) == latest,
+            })
+            .filter(|d| match &self.days {
+                Some(days) => days.contains(&d.day),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+/// The answer, or error, for one part of one day.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub answer: Result<String, String>,
+    /// How long solving the part took, not including parsing.
+    pub elapsed: Duration,
+    /// The recorded answer, if there is one.
+    pub expected: Option<String>,
+}
+
+/// How an outcome compares with the recorded answer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Error,
+    Unknown,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Error => "error",
+            Status::Unknown => "unknown",
+        })
+    }
+}
+
+impl Outcome {
+    fn new(day: &Day, part: Part, answer: Result<String, String>, elapsed: Duration) -> Self {
+        Outcome {
+            year: day.year,
+            day: day.day,
+            part,
+            answer,
+            elapsed,
+            expected: None,
+        }
+    }
+
+    /// A part that errors without a recorded answer is unknown rather than an
+    /// error, since it's most likely just not solved yet.
+    pub fn status(&self) -> Status {
+        match (&self.answer, &self.expected) {
+            (_, None) => Status::Unknown,
+            (Err(_), Some(_)) => Status::Error,
+            (Ok(answer), Some(expected)) if answer == expected => Status::Pass,
+            (Ok(_), Some(_)) => Status::Fail,
+        }
+    }
+}
+
+thread_local! {
+    /// Whether this thread is running inside `isolate`.
+    static ISOLATED: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+/// Runs `f`, turning a panic into an error so one broken day can't take the
+/// others down with it. The panic isn't printed, since its message ends up in
+/// the error, while panics outside `isolate` still go to the previous hook.
+pub(crate) fn isolate<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    QUIET_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !ISOLATED.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+
+    let outer = ISOLATED.with(|isolated| isolated.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    ISOLATED.with(|isolated| isolated.set(outer));
+
+    result.map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_string());
+        format!("Panicked: {}", message)
+    })
+}
+
+/// Parses the input once and solves each of `parts`. Panics are reported as
+/// errors.
+pub fn run_day(day: &Day, input: &str, parts: &[Part]) -> Vec<Outcome> {
+    let parsed = isolate(|| day.parse(input))
+        .and_then(|parsed| parsed.map_err(|e| format!("Couldn't parse the input: {}", e)));
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            return parts
+                .iter()
+                .map(|&part| Outcome::new(day, part, Err(error.clone()), Duration::default()))
+                .collect();
+        }
+    };
+
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = isolate(|| day.solve(&parsed, part))
+                .and_then(|answer| answer.map_err(|e| e.to_string()));
+            Outcome::new(day, part, answer, start.elapsed())
+        })
+        .collect()
+}
+
+/// Runs each of the day's examples for the selected parts, with the example's
+/// answer as the expected one.
+pub fn run_examples(day: &Day, parts: &[Part]) -> Vec<Outcome> {
+    day.examples()
+        .iter()
+        .filter(|example| parts.contains(&example.part))
+        .flat_map(|example| {
+            run_day(day, example.input, &[example.part])
+                .into_iter()
+                .map(move |mut outcome| {
+                    outcome.expected = Some(example.answer.to_string());
+                    outcome
+                })
+        })
+        .collect()
+}
+
+/// Checks every registered example, listing each one that didn't give its
+/// answer.
+pub fn check_examples(registry: &Registry) -> Result<(), String> {
+    let mut failures = Vec::new();
+
+    for day in registry.days() {
+        for (i, outcome) in run_examples(day, &Part::ALL).iter().enumerate() {
+            if outcome.status() != Status::Pass {
+                failures.push(format!(
+                    "{} day {} example {} part {}: expected {:?}, got {:?}",
+                    day.year,
+                    day.day,
+                    i + 1,
+                    outcome.part,
+                    outcome.expected.as_deref().unwrap_or_default(),
+                    outcome.answer
+                ));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures.join("\n"))
+    }
+}
+
+/// The outcomes of one day, with how long loading the input, parsing and
+/// solving took altogether.
+pub struct DayRun {
+    pub year: u16,
+    pub day: u8,
+    pub outcomes: Vec<Outcome>,
+    /// Whether the embedded input was used, so the answers file applies.
+    pub embedded: bool,
+    pub elapsed: Duration,
+}
+
+fn run_one(day: &Day, source: &Source, parts: &[Part]) -> DayRun {
+    let start = Instant::now();
+
+    let (outcomes, embedded) = match source.load(day) {
+        Ok(input) => (
+            run_day(day, &input, parts),
+            matches!(input, Cow::Borrowed(_)),
+        ),
+        Err(e) => {
+            let error = format!("Couldn't read the input: {}", e);
+            let outcomes = parts
+                .iter()
+                .map(|&part| Outcome::new(day, part, Err(error.clone()), Duration::default()))
+                .collect();
+            (outcomes, false)
+        }
+    };
+
+    DayRun {
+        year: day.year,
+        day: day.day,
+        outcomes,
+        embedded,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Runs the days on `jobs` threads, returning the runs in the same order as
+/// `days`.
+pub fn run_days(days: &[&Day], source: &Source, parts: &[Part], jobs: usize) -> Vec<DayRun> {
+    let next = AtomicUsize::new(0);
+    let runs = Mutex::new(Vec::with_capacity(days.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, days.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let day = match days.get(i) {
+                    Some(day) => day,
+                    None => break,
+                };
+
+                let run = run_one(day, source, parts);
+                runs.lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push((i, run));
+            });
+        }
+    });
+
+    let mut runs = runs.into_inner().unwrap_or_else(|e| e.into_inner());
+    runs.sort_by_key(|(i, _)| *i);
+    runs.into_iter().map(|(_, run)| run).collect()
+}
+
+/// Each day's time, slowest first, then the total time spent on days and the
+/// wall-clock time they took on `jobs` threads.
+pub fn timing_summary(runs: &[DayRun], wall: Duration, jobs: usize) -> String {
+    let mut sorted: Vec<&DayRun> = runs.iter().collect();
+    sorted.sort_by_key(|run| std::cmp::Reverse(run.elapsed));
+
+    let mut out = String::new();
+    let _ = writeln!(out, "Year  Day       Time");
+    let _ = writeln!(out, "----  ---  ---------");
+
+    for run in sorted {
+        let _ = writeln!(
+            out,
+            "{:>4}  {:>3}  {:>9}",
+            run.year,
+            run.day,
+            format_elapsed(run.elapsed)
+        );
+    }
+
+    let total: Duration = runs.iter().map(|r| r.elapsed).sum();
+    let _ = writeln!(
+        out,
+        "\nTotal {} for {} days, {} on {} thread{}",
+        format_elapsed(total),
+        runs.len(),
+        format_elapsed(wall),
+        jobs,
+        if jobs == 1 { "" } else { "s" }
+    );
+
+    out
+}
+
+/// Formats a duration with a unit suited to its size, e.g. `850.0µs`.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs_f64();
+
+    if secs < 1e-3 {
+        format!("{:.1}µs", secs * 1e6)
+    } else if secs < 1.0 {
+        format!("{:.2}ms", secs * 1e3)
+    } else {
+        format!("{:.2}s", secs)
+    }
+}
+
+/// Writes `prefix` followed by the first line of `text`, continuing any
+/// further lines aligned after the prefix.
+pub(crate) fn row(out: &mut String, prefix: &str, text: &str) {
+    let mut lines = text.lines();
+    let _ = writeln!(out, "{}{}", prefix, lines.next().unwrap_or_default());
+
+    for line in lines {
+        let _ = writeln!(out, "{:width$}{}", "", line, width = prefix.chars().count());
+    }
+}
+
+pub(crate) fn answer_text(outcome: &Outcome) -> String {
+    match &outcome.answer {
+        Ok(answer) => answer.clone(),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+/// Lays the outcomes out as a table. Multi-line answers continue on the
+/// following rows, aligned with the answer column.
+pub fn table(outcomes: &[Outcome]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Year  Day  Part  Answer");
+    let _ = writeln!(out, "----  ---  ----  ------");
+
+    for outcome in outcomes {
+        let prefix = format!(
+            "{:>4}  {:>3}  {:>4}  ",
+            outcome.year, outcome.day, outcome.part
+        );
+        row(&mut out, &prefix, &answer_text(outcome));
+    }
+
+    out
+}
+
+/// Like `table`, with each outcome's status and timing and the expected
+/// answer for failures, followed by a summary.
+pub fn check_table(outcomes: &[Outcome]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Year  Day  Part  Status        Time  Answer");
+    let _ = writeln!(out, "----  ---  ----  -------  ---------  ------");
+
+    let mut counts = BTreeMap::new();
+
+    for outcome in outcomes {
+        let status = outcome.status();
+        *counts.entry(status.to_string()).or_insert(0) += 1;
+
+        let prefix = format!(
+            "{:>4}  {:>3}  {:>4}  {:7}  {:>9}  ",
+            outcome.year,
+            outcome.day,
+            outcome.part,
+            status,
+            format_elapsed(outcome.elapsed)
+        );
+        row(&mut out, &prefix, &answer_text(outcome));
+
+        if let (Status::Fail, Some(expected)) = (status, &outcome.expected) {
+            let prefix = format!("{:width$}expected ", "", width = prefix.chars().count());
+            row(&mut out, &prefix, expected);
+        }
+    }
+
+    let total: Duration = outcomes.iter().map(|o| o.elapsed).sum();
+    let counts: Vec<String> = [Status::Pass, Status::Fail, Status::Error, Status::Unknown]
+        .iter()
+        .map(|s| format!("{} {}", counts.get(&s.to_string()).unwrap_or(&0), s))
+        .collect();
+    let _ = writeln!(out, "\n{} in {}", counts.join(", "), format_elapsed(total));
+
+    out
+}
+
+/// Loads the answers files of every year with a directory.
+fn load_answers(
+    registry: &Registry,
+    years: &[u16],
+) -> Result<BTreeMap<u16, Answers>, Box<dyn Error>> {
+    let mut answers = BTreeMap::new();
+
+    for &year in years {
+        if let Some(dir) = registry.dir(year) {
+            answers.insert(year, Answers::load(&dir.join("answers"))?);
+        }
+    }
+
+    Ok(answers)
+}
+
+/// Adds successful answers that aren't recorded yet, saving each year's file.
+fn record(
+    registry: &Registry,
+    answers: &mut BTreeMap<u16, Answers>,
+    outcomes: &[&Outcome],
+) -> Result<(), Box<dyn Error>> {
+    for (&year, known) in answers.iter_mut() {
+        let new: Vec<&&Outcome> = outcomes
+            .iter()
+            .filter(|o| o.year == year && o.answer.is_ok())
+            .filter(|o| known.get(o.day, o.part).is_none())
+            .collect();
+
+        if new.is_empty() {
+            continue;
+        }
+
+        for outcome in &new {
+            if let Ok(answer) = &outcome.answer {
+                known.insert(outcome.day, outcome.part, answer);
+            }
+        }
+
+        let path = registry
+            .dir(year)
+            .ok_or_else(|| format!("{} has no directory", year))?
+            .join("answers");
+        known.save(&path)?;
+        eprintln!("Recorded {} answers in {}", new.len(), path.display());
+    }
+
+    Ok(())
+}
+
+/// Prints parse errors, which include the usage, and exits.
+fn parsed<T>(options: Result<T, Box<dyn Error>>) -> T {
+    options.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    })
+}
+
+/// Runs the days selected by the process arguments and prints the results,
+/// exiting with a failure status if any part errored or, when checking,
+/// didn't match or errored despite having a recorded answer.
+pub fn main(name: &str, registry: &Registry) {
+    let mut args = std::env::args().skip(1).peekable();
+
+    let result = match args.peek().map(String::as_str) {
+        Some("fetch") => {
+            args.next();
+            let options = parsed(fetch::Options::parse(&format!("{} fetch", name), args));
+            fetch::run(options, registry)
+        }
+        Some("submit") => {
+            args.next();
+            let options = parsed(submit::Options::parse(&format!("{} submit", name), args));
+            submit::run(options, registry)
+        }
+        _ => run(name, parsed(Options::parse(name, args)), registry),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}: {}", name, e);
+        std::process::exit(2);
+    }
+}
+
+fn run(name: &str, mut options: Options, registry: &Registry) -> Result<(), Box<dyn Error>> {
+    if options.source == Source::Embedded {
+        if let Some(dir) = std::env::var_os(INPUTS_VAR) {
+            options.source = Source::Dir(dir.into());
+        }
+    }
+
+    let days = options.select(registry);
+    if days.is_empty() {
+        return Err("No registered days match the selection".into());
+    }
+
+    if options.source.is_single() && days.len() > 1 {
+        return Err("--input can only be used when one day is selected".into());
+    }
+
+    if options.watch {
+        let day = match days[..] {
+            [day] => day,
+            _ => return Err("--watch can only be used when one day is selected".into()),
+        };
+        let dir = registry
+            .dir(day.year)
+            .ok_or_else(|| format!("{} doesn't have a directory registered", day.year))?;
+
+        // Cargo sets this when running the binary, otherwise binaries are
+        // assumed to be named after their package.
+        let package = std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| name.to_string());
+
+        return watch::watch(name, &package, dir, day, options.part, &options.source);
+    }
+
+    if options.bench {
+        let mut measurements = Vec::new();
+
+        for day in days {
+            let input = options.source.load(day)?;
+            measurements.extend(bench::bench_day(
+                day,
+                &input,
+                &options.parts(),
+                options.bench_settings,
+            ));
+        }
+
+        let _ = io::stdout().write_all(bench::table(&measurements).as_bytes());
+        return Ok(());
+    }
+
+    let mut years: Vec<u16> = days.iter().map(|d| d.year).collect();
+    years.dedup();
+    let mut answers = load_answers(registry, &years)?;
+
+    let jobs = match options.jobs {
+        Some(0) => thread::available_parallelism().map_or(1, |n| n.get()),
+        Some(jobs) => jobs,
+        None => 1,
+    };
+
+    let start = Instant::now();
+    let runs = run_days(&days, &options.source, &options.parts(), jobs);
+    let wall = start.elapsed();
+
+    let mut outcomes = Vec::new();
+    let mut embedded = Vec::new();
+
+    for run in &runs {
+        for outcome in &run.outcomes {
+            let mut outcome = outcome.clone();
+            outcome.expected = answers
+                .get(&run.year)
+                .and_then(|a| a.get(run.day, outcome.part))
+                .filter(|_| run.embedded)
+                .map(String::from);
+
+            outcomes.push(outcome);
+            embedded.push(run.embedded);
+        }
+    }
+
+    let output = match options.format {
+        Format::Json => report::json(&outcomes),
+        Format::Csv => report::csv(&outcomes),
+        Format::Table if options.check => check_table(&outcomes),
+        Format::Table => table(&outcomes),
+    };
+    let _ = io::stdout().write_all(output.as_bytes());
+
+    let examples: Vec<Outcome> = if options.check {
+        days.iter()
+            .flat_map(|day| run_examples(day, &options.parts()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if !examples.is_empty() && options.format == Format::Table {
+        print!("\nExamples\n\n{}", check_table(&examples));
+    }
+
+    if options.jobs.is_some() {
+        eprint!("\n{}", timing_summary(&runs, wall, jobs));
+    }
+
+    if options.record {
+        let recordable: Vec<&Outcome> = outcomes
+            .iter()
+            .zip(embedded)
+            .filter(|(_, embedded)| *embedded)
+            .map(|(outcome, _)| outcome)
+            .collect();
+        record(registry, &mut answers, &recordable)?;
+    }
+
+    let failed = if options.check {
+        outcomes
+            .iter()
+            .chain(&examples)
+            .any(|o| o.status() == Status::Fail || o.status() == Status::Error)
+    } else {
+        outcomes.iter().any(|o| o.answer.is_err())
+    };
+
+    if failed {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Example, Solution};
+
+    struct Echo;
+
+    impl Solution for Echo {
+        type Input = String;
+        type Answer1 = String;
+        type Answer2 = usize;
+
+        const EXAMPLES: &'static [Example] = &[Example::part1("a", "a"), Example::part1("b", "c")];
+
+        fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            Ok(input.to_string())
+        }
+
+        fn part1(input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+            Ok(input.clone())
+        }
+
+        fn part2(_input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+            Err("Not solved".into())
+        }
+    }
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn selection() -> Result<(), Box<dyn Error>> {
+        let days: Selection<u8> = "1,3,5-7".parse()?;
+        assert!(days.contains(&1));
+        assert!(!days.contains(&2));
+        assert!(days.contains(&6));
+        assert!(!days.contains(&8));
+
+        assert!("7-5".parse::<Selection<u8>>().is_err());
+        assert!("x".parse::<Selection<u8>>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn options() -> Result<(), Box<dyn Error>> {
+        let options = Options::parse("aoc", args("--year 2019 --day 7 --part 2"))?;
+        assert_eq!(options.years, Some("2019".parse()?));
+        assert_eq!(options.days, Some("7".parse()?));
+        assert_eq!(options.part, Some(Part::Two));
+
+        assert!(Options::parse("aoc", args("--all"))?.all);
+        assert_eq!(
+            Options::parse("aoc", args("--all --format csv"))?.format,
+            Format::Csv
+        );
+        let bench = Options::parse("aoc", args("--all --bench --iterations 3 --warmup 0"))?;
+        assert!(bench.bench);
+        assert_eq!(
+            bench.bench_settings,
+            Settings {
+                warmup: 0,
+                iterations: 3
+            }
+        );
+        assert!(Options::parse("aoc", args("--day 3 --watch"))?.watch);
+        assert!(Options::parse("aoc", args("")).is_err());
+        assert!(Options::parse("aoc", args("--part 3 --all")).is_err());
+        assert!(Options::parse("aoc", args("--day")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn select() -> Result<(), Box<dyn Error>> {
+        let mut registry = Registry::new();
+        registry
+            .add::<Echo>(2018, 1, "")
+            .add::<Echo>(2019, 1, "")
+            .add::<Echo>(2019, 2, "")
+            .add::<Echo>(2019, 3, "");
+
+        let selected = |s| -> Result<Vec<(u16, u8)>, Box<dyn Error>> {
+            Ok(Options::parse("aoc", args(s))?
+                .select(&registry)
+                .iter()
+                .map(|d| (d.year, d.day))
+                .collect())
+        };
+
+        assert_eq!(selected("--day 2-3")?, vec![(2019, 2), (2019, 3)]);
+        assert_eq!(selected("--year 2018")?, vec![(2018, 1)]);
+        assert_eq!(selected("--all --day 1")?, vec![(2018, 1), (2019, 1)]);
+        assert_eq!(selected("--all")?.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sources() -> Result<(), Box<dyn Error>> {
+        let mut registry = Registry::new();
+        registry
+            .add::<Echo>(2018, 1, "embedded")
+            .add::<Echo>(2019, 1, "embedded");
+
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(dir.join("2019"))?;
+        fs::write(dir.join("2019").join("1"), "from dir")?;
+
+        let source = Source::Dir(dir.clone());
+        assert_eq!(source.load(registry.get(2019, 1).unwrap())?, "from dir");
+        assert_eq!(source.load(registry.get(2018, 1).unwrap())?, "embedded");
+
+        let file = Source::File(dir.join("2019").join("1"));
+        assert_eq!(file.load(registry.get(2018, 1).unwrap())?, "from dir");
+        assert!(Source::File(dir.join("missing"))
+            .load(registry.get(2018, 1).unwrap())
+            .is_err());
+
+        fs::remove_dir_all(dir)?;
+
+        let options = Options::parse("aoc", args("--day 1 --input -"))?;
+        assert_eq!(options.source, Source::Stdin);
+
+        Ok(())
+    }
+
+    #[test]
+    fn status() {
+        let mut registry = Registry::new();
+        registry.add::<Echo>(2019, 1, "42");
+
+        let day = registry.get(2019, 1).unwrap();
+        let mut outcomes = run_day(day, day.input, &Part::ALL);
+        assert_eq!(outcomes[0].status(), Status::Unknown);
+        assert_eq!(outcomes[1].status(), Status::Unknown);
+
+        outcomes[1].expected = Some("7".to_string());
+        assert_eq!(outcomes[1].status(), Status::Error);
+
+        outcomes[0].expected = Some("42".to_string());
+        assert_eq!(outcomes[0].status(), Status::Pass);
+
+        outcomes[0].expected = Some("43".to_string());
+        assert_eq!(outcomes[0].status(), Status::Fail);
+
+        let table = check_table(&outcomes);
+        assert!(table.contains("\n                                     expected 43\n"));
+        assert!(table.contains("0 pass, 1 fail, 1 error, 0 unknown in "));
+    }
+
+    struct Broken;
+
+    impl Solution for Broken {
+        type Input = ();
+        type Answer1 = u8;
+        type Answer2 = u8;
+
+        fn parse(_input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+            panic!("Broken")
+        }
+
+        fn part2(_input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn quiet_panics() {
+        assert_eq!(isolate(|| ISOLATED.with(Cell::get)), Ok(true));
+        assert_eq!(
+            isolate(|| -> () { panic!("Broken") }),
+            Err("Panicked: Broken".to_string())
+        );
+        assert!(!ISOLATED.with(Cell::get));
+    }
+
+    #[test]
+    fn parallel() {
+        let mut registry = Registry::new();
+        registry
+            .add::<Echo>(2018, 1, "a")
+            .add::<Broken>(2018, 4, "")
+            .add::<Echo>(2019, 1, "b")
+            .add::<Echo>(2019, 2, "c");
+
+        let days = Options::parse("aoc", args("--all"))
+            .unwrap()
+            .select(&registry);
+        let runs = run_days(&days, &Source::Embedded, &Part::ALL, 3);
+
+        let order: Vec<(u16, u8)> = runs.iter().map(|r| (r.year, r.day)).collect();
+        assert_eq!(order, vec![(2018, 1), (2018, 4), (2019, 1), (2019, 2)]);
+        assert!(runs.iter().all(|r| r.embedded));
+
+        let broken = &runs[1].outcomes;
+        assert_eq!(broken[0].answer, Err("Panicked: Broken".to_string()));
+        assert_eq!(broken[1].answer, Ok("2".to_string()));
+        assert_eq!(runs[3].outcomes[0].answer, Ok("c".to_string()));
+
+        let summary = timing_summary(&runs, Duration::from_millis(5), 3);
+        assert_eq!(summary.lines().count(), 2 + 4 + 2);
+        assert!(summary.ends_with(" for 4 days, 5.00ms on 3 threads\n"));
+    }
+
+    #[test]
+    fn examples() {
+        let mut registry = Registry::new();
+        registry.add::<Echo>(2019, 1, "");
+        let day = registry.get(2019, 1).unwrap();
+
+        let outcomes = run_examples(day, &Part::ALL);
+        let statuses: Vec<Status> = outcomes.iter().map(Outcome::status).collect();
+        assert_eq!(statuses, vec![Status::Pass, Status::Fail]);
+        assert!(run_examples(day, &[Part::Two]).is_empty());
+
+        assert_eq!(
+            check_examples(&registry),
+            Err("2019 day 1 example 2 part 1: expected \"c\", got Ok(\"b\")".to_string())
+        );
+    }
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+        type Answer1 = u32;
+        type Answer2 = u32;
+
+        fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            Ok(crate::parse::integers(input)?)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(_input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+            Err("Not solved".into())
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut registry = Registry::new();
+        registry.add::<Sum>(2019, 1, "1\n2,x\n");
+        let day = registry.get(2019, 1).unwrap();
+
+        let outcomes = run_day(day, day.input, &Part::ALL);
+        let error = "Couldn't parse the input: line 2: invalid digit found in string: \"x\"";
+        assert_eq!(outcomes[0].answer, Err(error.to_string()));
+        assert_eq!(outcomes[1].answer, Err(error.to_string()));
+    }
+
+    #[test]
+    fn table_layout() {
+        let mut registry = Registry::new();
+        registry.add::<Echo>(2019, 8, "ab\ncd");
+
+        let day = registry.get(2019, 8).unwrap();
+        let outcomes = run_day(day, day.input, &Part::ALL);
+        assert_eq!(
+            table(&outcomes),
+            "\
+Year  Day  Part  Answer
+----  ---  ----  ------
+2019    8     1  ab
+                 cd
+2019    8     2  error: Not solved
+"
+        );
+    }
+}
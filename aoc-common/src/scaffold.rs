@@ -0,0 +1,227 @@
+//! Creates the files for a new day and registers it with its year's crate.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+static TEMPLATE: &str = r#"use aoc_common::{Example, Solution};
+use std::error::Error;
+
+pub static INPUT: &str = include_str!("../res/{day}");
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    type Input = Vec<String>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    // The puzzle's examples, e.g. `Example::part1("...", "42")`.
+    const EXAMPLES: &'static [Example] = &[];
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn part1(_input: &Self::Input) -> Result<usize, Box<dyn Error>> {
+        Err("Part 1 isn't solved yet".into())
+    }
+
+    fn part2(_input: &Self::Input) -> Result<usize, Box<dyn Error>> {
+        Err("Part 2 isn't solved yet".into())
+    }
+}
+"#;
+
+/// The source of a new day's solution.
+pub fn template(day: u8) -> String {
+    TEMPLATE.replace("{day}", &day.to_string())
+}
+
+/// The directory of a year's crate within the workspace, e.g. `aoc19`.
+pub fn crate_dir(root: &Path, year: u16) -> PathBuf {
+    root.join(format!("aoc{:02}", year % 100))
+}
+
+/// The day number of a `.add::<dayN::DayN>(...)` registration line.
+fn registered_day(line: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix(".add::<day")?;
+    rest[..rest.find("::")?].parse().ok()
+}
+
+/// Adds `pub mod dayN;` to the module list, keeping it sorted, and registers
+/// the day after the registrations of the days before it.
+pub fn register(lib: &str, day: u8) -> Result<String, Box<dyn Error>> {
+    let mut lines: Vec<String> = lib.lines().map(String::from).collect();
+
+    let module = format!("day{}", day);
+    let mods: Vec<(usize, String)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| {
+            Some((
+                i,
+                l.strip_prefix("pub mod ")?
+                    .trim_end_matches(';')
+                    .to_string(),
+            ))
+        })
+        .collect();
+    let last_mod = mods.last().ok_or("Couldn't find the module list")?.0;
+
+    if mods.iter().any(|(_, name)| *name == module) {
+        return Err(format!("{} is already a module", module).into());
+    }
+
+    let position = mods
+        .iter()
+        .find(|(_, name)| *name > module)
+        .map_or(last_mod + 1, |(i, _)| *i);
+    lines.insert(position, format!("pub mod {};", module));
+
+    let adds: Vec<usize> = (0..lines.len())
+        .filter(|&i| registered_day(&lines[i]).is_some())
+        .collect();
+
+    // Register after the last earlier day, otherwise after whatever comes
+    // before the first registration.
+    let anchor = adds
+        .iter()
+        .rev()
+        .find(|&&i| registered_day(&lines[i]) < Some(day))
+        .copied()
+        .or_else(|| adds.first().map(|&i| i - 1))
+        .or_else(|| lines.iter().position(|l| l.trim().starts_with(".set_dir(")))
+        .ok_or("Couldn't find where days are registered")?;
+
+    let indent_of =
+        |line: &str| -> String { line.chars().take_while(|c| c.is_whitespace()).collect() };
+    let indent = indent_of(&lines[adds.first().copied().unwrap_or(anchor)]);
+    let mut line = format!(
+        "{}.add::<day{1}::Day{1}>(YEAR, {1}, day{1}::INPUT)",
+        indent, day
+    );
+
+    // The last call in the chain ends the statement.
+    if lines[anchor].ends_with(';') {
+        lines[anchor].pop();
+        line.push(';');
+    }
+
+    lines.insert(anchor + 1, line);
+
+    let mut registered = lines.join("\n");
+    registered.push('\n');
+    Ok(registered)
+}
+
+/// Writes `src/dayN.rs` and an empty `res/N` in the year's crate and registers
+/// the day in its `lib.rs`. Returns the paths that were created.
+pub fn scaffold(crate_dir: &Path, day: u8) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let source = crate_dir.join("src").join(format!("day{}.rs", day));
+    let input = crate_dir.join("res").join(day.to_string());
+    let lib = crate_dir.join("src").join("lib.rs");
+
+    if source.exists() {
+        return Err(format!("{} already exists", source.display()).into());
+    }
+
+    let registered = register(
+        &fs::read_to_string(&lib).map_err(|e| format!("{}: {}", lib.display(), e))?,
+        day,
+    )?;
+
+    fs::write(&source, template(day))?;
+    fs::write(&lib, registered)?;
+
+    let mut created = vec![source];
+
+    if !input.exists() {
+        fs::create_dir_all(crate_dir.join("res"))?;
+        fs::write(&input, "")?;
+        created.push(input);
+    }
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static LIB: &str = "\
+use aoc_common::Registry;
+
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod intcode;
+
+pub const YEAR: u16 = 2019;
+
+pub fn register(registry: &mut Registry) {
+    registry
+        .set_dir(YEAR, env!(\"CARGO_MANIFEST_DIR\"))
+        .add::<day1::Day1>(YEAR, 1, day1::INPUT)
+        .add::<day2::Day2>(YEAR, 2, day2::INPUT)
+        .add::<day10::Day10>(YEAR, 10, day10::INPUT);
+}
+";
+
+    #[test]
+    fn registers_in_order() -> Result<(), Box<dyn Error>> {
+        let lib = register(LIB, 3)?;
+        assert!(lib.contains("pub mod day2;\npub mod day3;\npub mod intcode;"));
+        assert!(lib.contains(
+            "day2::INPUT)\n        .add::<day3::Day3>(YEAR, 3, day3::INPUT)\n        .add::<day10"
+        ));
+
+        let lib = register(LIB, 11)?;
+        assert!(lib.contains("pub mod day10;\npub mod day11;\npub mod day2;"));
+        assert!(
+            lib.contains("day10::INPUT)\n        .add::<day11::Day11>(YEAR, 11, day11::INPUT);\n}")
+        );
+
+        let lib = register(&register(LIB, 11)?, 12)?;
+        assert!(
+            lib.contains("day11::INPUT)\n        .add::<day12::Day12>(YEAR, 12, day12::INPUT);")
+        );
+
+        assert!(register(LIB, 2).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn first_day() -> Result<(), Box<dyn Error>> {
+        let lib = LIB
+            .lines()
+            .filter(|l| !l.contains("::Day"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .replace("CARGO_MANIFEST_DIR\"))", "CARGO_MANIFEST_DIR\"));");
+
+        let lib = register(&lib, 3)?;
+        assert!(lib.contains(")\n        .add::<day3::Day3>(YEAR, 3, day3::INPUT);\n}"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn creates_files() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src"))?;
+        fs::write(dir.join("src").join("lib.rs"), LIB)?;
+
+        let created = scaffold(&dir, 4)?;
+        assert_eq!(created, vec![dir.join("src/day4.rs"), dir.join("res/4")]);
+        assert_eq!(fs::read_to_string(dir.join("src/day4.rs"))?, template(4));
+        assert_eq!(fs::read_to_string(dir.join("res/4"))?, "");
+        assert!(fs::read_to_string(dir.join("src/lib.rs"))?.contains("pub mod day4;"));
+
+        assert!(scaffold(&dir, 4).is_err());
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
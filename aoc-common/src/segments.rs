@@ -0,0 +1,551 @@
+//! Finds where wires made of horizontal and vertical segments meet.
+//!
+//! Crossings between horizontal and vertical segments are found with a sweep
+//! from left to right: horizontal segments are active between their ends,
+//! kept ordered by y, and each vertical segment looks up the active ones
+//! within its span. Collinear segments are grouped by the line they're on
+//! and sorted along it, so only those that overlap are compared. That's
+//! `O((n + k) log n)` for `n` segments meeting `k` times, rather than
+//! comparing every pair.
+
+use crate::point::{Direction, Point2};
+use std::collections::BTreeSet;
+
+pub type Point = Point2<i64>;
+
+/// A horizontal or vertical segment, including both ends.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Segment {
+    pub from: Point,
+    pub to: Point,
+}
+
+impl Segment {
+    pub fn new(from: Point, to: Point) -> Self {
+        Segment { from, to }
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.from.x == self.to.x && self.from.y != self.to.y
+    }
+
+    pub fn len(&self) -> i64 {
+        self.from.manhattan_distance(self.to)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.from == self.to
+    }
+
+    /// The smallest and largest x.
+    fn xs(&self) -> (i64, i64) {
+        (self.from.x.min(self.to.x), self.from.x.max(self.to.x))
+    }
+
+    /// The smallest and largest y.
+    fn ys(&self) -> (i64, i64) {
+        (self.from.y.min(self.to.y), self.from.y.max(self.to.y))
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        let ((x1, x2), (y1, y2)) = (self.xs(), self.ys());
+        (x1..=x2).contains(&p.x) && (y1..=y2).contains(&p.y)
+    }
+
+    /// The point on the segment closest to `p`.
+    pub fn nearest(&self, p: Point) -> Point {
+        let ((x1, x2), (y1, y2)) = (self.xs(), self.ys());
+        Point::new(p.x.clamp(x1, x2), p.y.clamp(y1, y2))
+    }
+
+    /// One step along the segment from `from` towards `to`.
+    fn step_in(from: Point, to: Point) -> Point {
+        let delta = to - from;
+        from + Point::new(delta.x.signum(), delta.y.signum())
+    }
+}
+
+/// A path made of horizontal and vertical segments, each starting where the
+/// previous one ends.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Wire {
+    segments: Vec<Segment>,
+    /// How far along the wire each segment starts.
+    steps: Vec<i64>,
+}
+
+impl Wire {
+    /// A wire starting at `start` and following the moves. Moves of no length
+    /// are skipped.
+    pub fn from_moves(start: Point, moves: impl IntoIterator<Item = (Direction, i64)>) -> Self {
+        let mut wire = Wire::default();
+        let mut position = start;
+
+        for (dir, length) in moves {
+            let next = position + dir.delta() * length;
+            wire.push(Segment::new(position, next));
+            position = next;
+        }
+
+        wire
+    }
+
+    /// A wire through each of the points in turn. Fails if a pair of points
+    /// aren't in line horizontally or vertically.
+    pub fn from_points(points: &[Point]) -> Result<Self, String> {
+        let mut wire = Wire::default();
+
+        for pair in points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if from.x != to.x && from.y != to.y {
+                return Err(format!(
+                    "{},{} to {},{} isn't horizontal or vertical",
+                    from.x, from.y, to.x, to.y
+                ));
+            }
+            wire.push(Segment::new(from, to));
+        }
+
+        Ok(wire)
+    }
+
+    fn push(&mut self, segment: Segment) {
+        if segment.is_empty() {
+            return;
+        }
+
+        let steps = match (self.segments.last(), self.steps.last()) {
+            (Some(last), Some(steps)) => steps + last.len(),
+            _ => 0,
+        };
+        self.segments.push(segment);
+        self.steps.push(steps);
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+/// Where an intersection is on one of the wires.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Hit {
+    /// The wire's index.
+    pub wire: usize,
+    /// The segment's index within the wire.
+    pub segment: usize,
+    start: Point,
+    steps: i64,
+}
+
+impl Hit {
+    /// How far along the wire a point on the segment is.
+    pub fn steps_to(&self, p: Point) -> i64 {
+        self.steps + self.start.manhattan_distance(p)
+    }
+}
+
+/// Two segments meeting, either at a single point or, if they're collinear,
+/// along a stretch of both. `a` is the segment from the lower numbered wire,
+/// or the earlier segment if it's a wire meeting itself.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Intersection {
+    /// Where they meet, `from` is the end with the lower coordinates.
+    pub at: Segment,
+    pub a: Hit,
+    pub b: Hit,
+}
+
+impl Intersection {
+    pub fn is_point(&self) -> bool {
+        self.at.is_empty()
+    }
+
+    /// Whether the wires are different wires, not one crossing itself.
+    pub fn is_between_wires(&self) -> bool {
+        self.a.wire != self.b.wire
+    }
+}
+
+/// A segment as its wire and its index within the wire.
+type Id = (usize, usize);
+
+fn segment(wires: &[Wire], (wire, index): Id) -> Segment {
+    wires[wire].segments[index]
+}
+
+fn hit(wires: &[Wire], (wire, index): Id) -> Hit {
+    Hit {
+        wire,
+        segment: index,
+        start: wires[wire].segments[index].from,
+        steps: wires[wire].steps[index],
+    }
+}
+
+/// Records the segments meeting at `at`. The point where one segment of a wire
+/// joins the next isn't an intersection, though the wire doubling back over
+/// itself is.
+fn record(wires: &[Wire], first: Id, second: Id, at: Segment, found: &mut Vec<Intersection>) {
+    let (a, b) = (first.min(second), first.max(second));
+    let mut at = at;
+
+    if a.0 == b.0 && a.1 + 1 == b.1 {
+        let joint = segment(wires, a).to;
+        if at.is_empty() {
+            return;
+        }
+        if at.from == joint {
+            at.from = Segment::step_in(at.from, at.to);
+        } else if at.to == joint {
+            at.to = Segment::step_in(at.to, at.from);
+        }
+    }
+
+    found.push(Intersection {
+        at,
+        a: hit(wires, a),
+        b: hit(wires, b),
+    });
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Event {
+    // At the same x, horizontal segments starting there are added before
+    // vertical ones are checked, and ones ending there are removed after.
+    Start,
+    Vertical,
+    End,
+}
+
+/// Horizontal segments crossing vertical ones.
+fn crossings(wires: &[Wire], horizontal: &[Id], vertical: &[Id], found: &mut Vec<Intersection>) {
+    let mut events = Vec::with_capacity(horizontal.len() * 2 + vertical.len());
+
+    for &id in horizontal {
+        let (x1, x2) = segment(wires, id).xs();
+        events.push((x1, Event::Start, id));
+        events.push((x2, Event::End, id));
+    }
+    for &id in vertical {
+        events.push((segment(wires, id).from.x, Event::Vertical, id));
+    }
+    events.sort_unstable();
+
+    let mut active = BTreeSet::new();
+
+    for (x, event, id) in events {
+        let y = segment(wires, id).from.y;
+
+        match event {
+            Event::Start => {
+                active.insert((y, id));
+            }
+            Event::End => {
+                active.remove(&(y, id));
+            }
+            Event::Vertical => {
+                let (y1, y2) = segment(wires, id).ys();
+                let within = (y1, (0, 0))..=(y2, (usize::MAX, usize::MAX));
+
+                for &(y, other) in active.range(within) {
+                    let p = Point::new(x, y);
+                    record(wires, other, id, Segment::new(p, p), found);
+                }
+            }
+        }
+    }
+}
+
+/// Collinear segments overlapping, `line` giving the line a segment is on and
+/// `span` how far along it the segment reaches.
+fn overlaps(
+    wires: &[Wire],
+    ids: &[Id],
+    line: impl Fn(&Segment) -> i64,
+    span: impl Fn(&Segment) -> (i64, i64),
+    point: impl Fn(i64, i64) -> Point,
+    found: &mut Vec<Intersection>,
+) {
+    let mut sorted: Vec<(i64, i64, i64, Id)> = ids
+        .iter()
+        .map(|&id| {
+            let s = segment(wires, id);
+            let (start, end) = span(&s);
+            (line(&s), start, end, id)
+        })
+        .collect();
+    sorted.sort_unstable();
+
+    // The segments on the current line which reach the current start.
+    let mut active: Vec<(i64, Id)> = Vec::new();
+    let mut current = None;
+
+    for (line, start, end, id) in sorted {
+        if current != Some(line) {
+            active.clear();
+            current = Some(line);
+        }
+
+        active.retain(|&(other_end, _)| other_end >= start);
+
+        for &(other_end, other) in &active {
+            let at = Segment::new(point(line, start), point(line, end.min(other_end)));
+            record(wires, other, id, at, found);
+        }
+
+        active.push((end, id));
+    }
+}
+
+/// Every place any of the wires meet each other or themselves.
+pub fn intersections(wires: &[Wire]) -> Vec<Intersection> {
+    let mut horizontal = Vec::new();
+    let mut vertical = Vec::new();
+
+    for (w, wire) in wires.iter().enumerate() {
+        for (i, segment) in wire.segments.iter().enumerate() {
+            if segment.is_vertical() {
+                vertical.push((w, i));
+            } else {
+                horizontal.push((w, i));
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    crossings(wires, &horizontal, &vertical, &mut found);
+    overlaps(
+        wires,
+        &horizontal,
+        |s| s.from.y,
+        Segment::xs,
+        |y, x| Point::new(x, y),
+        &mut found,
+    );
+    overlaps(
+        wires,
+        &vertical,
+        |s| s.from.x,
+        Segment::ys,
+        Point::new,
+        &mut found,
+    );
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Xorshift;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    fn wire(points: &[(i64, i64)]) -> Wire {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Wire::from_points(&points).unwrap()
+    }
+
+    fn segment(from: (i64, i64), to: (i64, i64)) -> Segment {
+        Segment::new(Point::new(from.0, from.1), Point::new(to.0, to.1))
+    }
+
+    /// Every point of every intersection, with the wires and segments meeting
+    /// there and how far along each wire it is.
+    fn expand(found: &[Intersection]) -> BTreeSet<(Point, Id, Id, i64, i64)> {
+        let mut points = BTreeSet::new();
+
+        for i in found {
+            let mut p = i.at.from;
+            loop {
+                points.insert((
+                    p,
+                    (i.a.wire, i.a.segment),
+                    (i.b.wire, i.b.segment),
+                    i.a.steps_to(p),
+                    i.b.steps_to(p),
+                ));
+                if p == i.at.to {
+                    break;
+                }
+                p = Segment::step_in(p, i.at.to);
+            }
+        }
+
+        points
+    }
+
+    /// The same as `expand(intersections(wires))`, by visiting every point of
+    /// every wire.
+    fn brute_force(wires: &[Wire]) -> BTreeSet<(Point, Id, Id, i64, i64)> {
+        let mut visits: BTreeMap<Point, Vec<(Id, i64)>> = BTreeMap::new();
+
+        for (w, wire) in wires.iter().enumerate() {
+            for (i, s) in wire.segments.iter().enumerate() {
+                let mut p = s.from;
+                loop {
+                    let steps = wire.steps[i] + s.from.manhattan_distance(p);
+                    visits.entry(p).or_default().push(((w, i), steps));
+                    if p == s.to {
+                        break;
+                    }
+                    p = Segment::step_in(p, s.to);
+                }
+            }
+        }
+
+        let mut points = BTreeSet::new();
+        for (p, visits) in visits {
+            for (i, &(a, a_steps)) in visits.iter().enumerate() {
+                for &(b, b_steps) in &visits[i + 1..] {
+                    let joint = a.0 == b.0 && (a.1 + 1 == b.1 || b.1 + 1 == a.1);
+                    let at_joint = joint && {
+                        let first = a.min(b);
+                        wires[first.0].segments[first.1].to == p
+                    };
+                    if !at_joint {
+                        let ((a, a_steps), (b, b_steps)) = if a < b {
+                            ((a, a_steps), (b, b_steps))
+                        } else {
+                            ((b, b_steps), (a, a_steps))
+                        };
+                        points.insert((p, a, b, a_steps, b_steps));
+                    }
+                }
+            }
+        }
+
+        points
+    }
+
+    #[test]
+    fn crossings() {
+        let wires = [
+            Wire::from_moves(
+                Point::default(),
+                vec![
+                    (Direction::Right, 8),
+                    (Direction::Up, 5),
+                    (Direction::Left, 5),
+                    (Direction::Down, 3),
+                ],
+            ),
+            wire(&[(0, 0), (0, -7), (6, -7), (6, -3), (2, -3)]),
+        ];
+
+        let mut found: Vec<(Point, i64)> = intersections(&wires)
+            .iter()
+            .filter(|i| i.is_between_wires() && i.at.from != Point::default())
+            .map(|i| (i.at.from, i.a.steps_to(i.at.from) + i.b.steps_to(i.at.from)))
+            .collect();
+        found.sort();
+
+        assert_eq!(
+            found,
+            vec![(Point::new(3, -3), 40), (Point::new(6, -5), 30)]
+        );
+    }
+
+    #[test]
+    fn overlaps() {
+        let wires = [
+            wire(&[(0, 0), (10, 0)]),
+            wire(&[(4, 5), (4, 0), (15, 0)]),
+            wire(&[(12, -3), (12, 3)]),
+        ];
+
+        let found = intersections(&wires);
+        assert_eq!(found.len(), 3);
+
+        let overlap = found
+            .iter()
+            .find(|i| (i.a.wire, i.b.wire) == (0, 1) && !i.is_point())
+            .unwrap();
+        assert_eq!(overlap.at, segment((4, 0), (10, 0)));
+        assert_eq!(overlap.a.steps_to(overlap.at.to), 10);
+        assert_eq!(overlap.b.steps_to(overlap.at.to), 11);
+
+        // The vertical part of the second wire ends on the first.
+        assert!(found
+            .iter()
+            .any(|i| i.is_point() && (i.a.wire, i.a.segment, i.b.wire) == (0, 0, 1)));
+        assert!(found
+            .iter()
+            .any(|i| i.at == segment((12, 0), (12, 0)) && (i.a.wire, i.b.wire) == (1, 2)));
+    }
+
+    #[test]
+    fn self_intersections() {
+        // Joints don't count, crossing itself or doubling back does.
+        let square = wire(&[(0, 0), (4, 0), (4, 4), (2, 4), (2, -2)]);
+        let found = intersections(&[square]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].at, segment((2, 0), (2, 0)));
+        assert_eq!((found[0].a.segment, found[0].b.segment), (0, 3));
+
+        let back = wire(&[(0, 0), (5, 0), (2, 0)]);
+        let found = intersections(&[back]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].at, segment((2, 0), (4, 0)));
+
+        assert!(intersections(&[wire(&[(0, 0), (3, 0), (7, 0), (7, 2)])]).is_empty());
+    }
+
+    #[test]
+    fn large_coordinates() {
+        let far = 1 << 40;
+        let wires = [
+            wire(&[(-far, 7), (far, 7)]),
+            wire(&[(far - 1, -far), (far - 1, far)]),
+        ];
+
+        let found = intersections(&wires);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].at.from, Point::new(far - 1, 7));
+        assert_eq!(found[0].a.steps_to(found[0].at.from), 2 * far - 1);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        // Random moves, so the wires wander over each other a lot.
+        let mut random = Xorshift::default();
+
+        for _ in 0..50 {
+            let wires: Vec<Wire> = (0..3)
+                .map(|_| {
+                    let moves: Vec<(Direction, i64)> = (0..12)
+                        .map(|_| {
+                            (
+                                Direction::ALL[random.below(4) as usize],
+                                random.below(6) as i64,
+                            )
+                        })
+                        .collect();
+                    Wire::from_moves(Point::default(), moves)
+                })
+                .collect();
+
+            assert_eq!(expand(&intersections(&wires)), brute_force(&wires));
+        }
+    }
+
+    #[test]
+    fn wires() {
+        assert!(Wire::from_points(&[Point::new(0, 0), Point::new(1, 1)]).is_err());
+
+        let w = Wire::from_moves(
+            Point::new(1, 1),
+            vec![
+                (Direction::Left, 0),
+                (Direction::Down, 2),
+                (Direction::Up, 0),
+            ],
+        );
+        assert_eq!(w.segments(), &[segment((1, 1), (1, 3))]);
+        assert!(segment((1, 1), (1, 3)).contains(Point::new(1, 2)));
+        assert_eq!(
+            segment((1, 1), (1, 3)).nearest(Point::new(-5, 9)),
+            Point::new(1, 3)
+        );
+    }
+}
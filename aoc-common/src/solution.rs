@@ -0,0 +1,228 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// A day's puzzle. The input is parsed once and then shared by both parts.
+pub trait Solution {
+    type Input: 'static;
+    type Answer1: fmt::Display;
+    type Answer2: fmt::Display;
+
+    /// The puzzle's worked examples, checked by each year's tests and by the
+    /// runner's `--check`.
+    const EXAMPLES: &'static [Example] = &[];
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>>;
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>>;
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>>;
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+impl std::str::FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            other => Err(format!("There's no part {:?}", other)),
+        }
+    }
+}
+
+/// An example input and the answer it should give for one part.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Example {
+    pub part: Part,
+    pub input: &'static str,
+    pub answer: &'static str,
+}
+
+impl Example {
+    pub const fn part1(input: &'static str, answer: &'static str) -> Self {
+        Example {
+            part: Part::One,
+            input,
+            answer,
+        }
+    }
+
+    pub const fn part2(input: &'static str, answer: &'static str) -> Self {
+        Example {
+            part: Part::Two,
+            input,
+            answer,
+        }
+    }
+}
+
+/// A parsed input, only usable with the `Day` that produced it.
+pub struct Parsed(Box<dyn Any>);
+
+/// `Solution` with its types erased so different days can share a registry.
+trait Erased: Send + Sync {
+    fn parse(&self, input: &str) -> Result<Parsed, Box<dyn Error>>;
+    fn solve(&self, parsed: &Parsed, part: Part) -> Result<String, Box<dyn Error>>;
+    fn examples(&self) -> &'static [Example];
+}
+
+struct Wrapper<S>(PhantomData<fn() -> S>);
+
+impl<S: Solution> Erased for Wrapper<S> {
+    fn parse(&self, input: &str) -> Result<Parsed, Box<dyn Error>> {
+        Ok(Parsed(Box::new(S::parse(input)?)))
+    }
+
+    fn solve(&self, parsed: &Parsed, part: Part) -> Result<String, Box<dyn Error>> {
+        let input = parsed
+            .0
+            .downcast_ref::<S::Input>()
+            .ok_or("The input was parsed by a different day")?;
+
+        match part {
+            Part::One => Ok(S::part1(input)?.to_string()),
+            Part::Two => Ok(S::part2(input)?.to_string()),
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        S::EXAMPLES
+    }
+}
+
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    /// The puzzle input embedded in the binary.
+    pub input: &'static str,
+    solution: Box<dyn Erased>,
+}
+
+impl Day {
+    pub fn parse(&self, input: &str) -> Result<Parsed, Box<dyn Error>> {
+        self.solution.parse(input)
+    }
+
+    pub fn solve(&self, parsed: &Parsed, part: Part) -> Result<String, Box<dyn Error>> {
+        self.solution.solve(parsed, part)
+    }
+
+    pub fn examples(&self) -> &'static [Example] {
+        self.solution.examples()
+    }
+}
+
+/// Every registered day, keyed and ordered by (year, day).
+#[derive(Default)]
+pub struct Registry {
+    days: BTreeMap<(u16, u8), Day>,
+    dirs: BTreeMap<u16, PathBuf>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    pub fn add<S: Solution + 'static>(
+        &mut self,
+        year: u16,
+        day: u8,
+        input: &'static str,
+    ) -> &mut Self {
+        let day = Day {
+            year,
+            day,
+            input,
+            solution: Box::new(Wrapper::<S>(PhantomData)),
+        };
+
+        self.days.insert((year, day.day), day);
+        self
+    }
+
+    /// Sets the directory of the crate holding a year's solutions, which is
+    /// where its answers file lives.
+    pub fn set_dir(&mut self, year: u16, dir: impl Into<PathBuf>) -> &mut Self {
+        self.dirs.insert(year, dir.into());
+        self
+    }
+
+    pub fn dir(&self, year: u16) -> Option<&Path> {
+        self.dirs.get(&year).map(PathBuf::as_path)
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Option<&Day> {
+        self.days.get(&(year, day))
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = &Day> {
+        self.days.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<i32>;
+        type Answer1 = i32;
+        type Answer2 = String;
+
+        fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            Ok(input
+                .split(',')
+                .map(|s| s.parse::<i32>())
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(_input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+            Err("Not solved".into())
+        }
+    }
+
+    #[test]
+    fn registry() -> Result<(), Box<dyn Error>> {
+        let mut registry = Registry::new();
+        registry.add::<Sum>(2019, 2, "1,2").add::<Sum>(2018, 9, "3");
+
+        let order: Vec<(u16, u8)> = registry.days().map(|d| (d.year, d.day)).collect();
+        assert_eq!(order, vec![(2018, 9), (2019, 2)]);
+
+        let day = registry.get(2019, 2).unwrap();
+        let parsed = day.parse(day.input)?;
+        assert_eq!(day.solve(&parsed, Part::One)?, "3");
+        assert!(day.solve(&parsed, Part::Two).is_err());
+        assert!(day.parse("x").is_err());
+
+        Ok(())
+    }
+}
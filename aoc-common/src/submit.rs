@@ -0,0 +1,435 @@
+//! Submits answers to the puzzle site and records what it said in the year's
+//! answers file. Uses the same config as `fetch`.
+
+use crate::answers::Answers;
+use crate::fetch::{Client, Config};
+use crate::runner;
+use crate::solution::{Part, Registry};
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+static USAGE: &str = "\
+Usage: {name} [options]
+
+Options:
+    -y, --year <year>     The puzzle's year, the most recent registered year by
+                          default
+    -d, --day <day>       The puzzle's day
+    -p, --part <part>     The part to submit, 1 or 2
+    -a, --answer <value>  Submit this instead of solving the embedded input
+    -c, --config <path>   The config file, $AOC_CONFIG or ~/.config/aoc/config
+                          by default
+    -h, --help            Print this message
+
+Right answers are added to the year's answers file and wrong ones are noted so
+they aren't submitted again.";
+
+fn usage(name: &str) -> String {
+    USAGE.replace("{name}", name)
+}
+
+/// What the site made of an answer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// An answer was submitted too recently, with how long is left to wait.
+    RateLimited(String),
+    /// The part has already been solved, so answers aren't checked.
+    AlreadySolved,
+    /// The text of a response that wasn't recognised.
+    Unknown(String),
+}
+
+impl Verdict {
+    /// Whether the answer is known to be wrong.
+    pub fn is_rejection(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Right => f.write_str("right"),
+            Verdict::Wrong => f.write_str("wrong"),
+            Verdict::TooHigh => f.write_str("too high"),
+            Verdict::TooLow => f.write_str("too low"),
+            Verdict::RateLimited(wait) => write!(f, "rate limited, {} left to wait", wait),
+            Verdict::AlreadySolved => f.write_str("already solved"),
+            Verdict::Unknown(text) => write!(f, "unrecognised response: {}", text),
+        }
+    }
+}
+
+/// The text of the page's `<article>`, or the whole page if there isn't one,
+/// without tags and with whitespace collapsed.
+fn article_text(html: &str) -> String {
+    let article = match (html.find("<article"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+
+    let mut text = String::with_capacity(article.len());
+    let mut in_tag = false;
+
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Recognises the site's responses to an answer.
+pub fn parse_verdict(html: &str) -> Verdict {
+    let text = article_text(html);
+
+    if text.contains("That's the right answer") {
+        Verdict::Right
+    } else if text.contains("That's not the right answer") {
+        if text.contains("too high") {
+            Verdict::TooHigh
+        } else if text.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        let wait = text
+            .split_once(" left to wait")
+            .and_then(|(before, _)| before.rsplit_once("have "))
+            .map_or("some time", |(_, wait)| wait);
+        Verdict::RateLimited(wait.to_string())
+    } else if text.contains("Did you already complete it") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown(text)
+    }
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn form_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Posts the answer and returns the site's verdict.
+pub fn submit(
+    client: &mut Client,
+    year: u16,
+    day: u8,
+    part: Part,
+    answer: &str,
+) -> Result<Verdict, Box<dyn Error>> {
+    let response = client.post(
+        &format!("/{}/day/{}/answer", year, day),
+        &format!("level={}&answer={}", part, form_encode(answer)),
+    )?;
+
+    if !response.is_success() {
+        return Err(format!(
+            "The server responded with {}: {}",
+            response.status,
+            article_text(&response.body)
+        )
+        .into());
+    }
+
+    Ok(parse_verdict(&response.body))
+}
+
+/// Submits the answer unless the answers file already settles it, then
+/// records the verdict there. An answer already known to be right is reported
+/// as right without asking the site.
+pub fn submit_and_record(
+    client: &mut Client,
+    answers_path: &Path,
+    year: u16,
+    day: u8,
+    part: Part,
+    answer: &str,
+) -> Result<Verdict, Box<dyn Error>> {
+    let mut answers = Answers::load(answers_path)?;
+
+    match answers.get(day, part) {
+        Some(known) if known == answer => return Ok(Verdict::Right),
+        Some(known) => {
+            return Err(format!(
+                "{} day {} part {} was already solved with {:?}",
+                year, day, part, known
+            )
+            .into())
+        }
+        None => {}
+    }
+
+    if let Some(reason) = answers.rejection(day, part, answer) {
+        return Err(format!("{:?} was already rejected as {}", answer, reason).into());
+    }
+
+    let verdict = submit(client, year, day, part, answer)?;
+
+    if verdict == Verdict::Right {
+        answers.insert(day, part, answer);
+    } else if verdict.is_rejection() {
+        answers.reject(day, part, answer, &verdict.to_string());
+    } else {
+        return Ok(verdict);
+    }
+
+    answers.save(answers_path)?;
+    eprintln!("Recorded the answer in {}", answers_path.display());
+
+    Ok(verdict)
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    pub part: Option<Part>,
+    pub answer: Option<String>,
+    pub config: Option<PathBuf>,
+}
+
+impl Options {
+    /// Parses the arguments following the subcommand. `name` is only used in
+    /// the usage message.
+    pub fn parse<I>(name: &str, args: I) -> Result<Options, Box<dyn Error>>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+            match arg.as_str() {
+                "-y" | "--year" => options.year = Some(value()?.parse()?),
+                "-d" | "--day" => options.day = Some(value()?.parse()?),
+                "-p" | "--part" => options.part = Some(value()?.parse()?),
+                "-a" | "--answer" => options.answer = Some(value()?),
+                "-c" | "--config" => options.config = Some(value()?.into()),
+                "-h" | "--help" => {
+                    println!("{}", usage(name));
+                    std::process::exit(0);
+                }
+                _ => return Err(format!("Unexpected argument {}\n\n{}", arg, usage(name)).into()),
+            }
+        }
+
+        if options.day.is_none() || options.part.is_none() {
+            return Err(usage(name).into());
+        }
+
+        Ok(options)
+    }
+}
+
+/// Exits with 1 if the answer wasn't accepted.
+pub fn run(options: Options, registry: &Registry) -> Result<(), Box<dyn Error>> {
+    let year = options
+        .year
+        .or_else(|| registry.days().map(|d| d.year).max())
+        .ok_or("There are no registered years")?;
+    let day = options.day.ok_or("No day selected")?;
+    let part = options.part.ok_or("No part selected")?;
+    let dir = registry
+        .dir(year)
+        .ok_or_else(|| format!("{} doesn't have a directory registered", year))?;
+
+    let answer = match options.answer {
+        Some(answer) => answer,
+        None => {
+            let solution = registry
+                .get(year, day)
+                .ok_or_else(|| format!("{} day {} isn't registered", year, day))?;
+
+            runner::run_day(solution, solution.input, &[part])
+                .remove(0)
+                .answer?
+        }
+    };
+
+    let config = options
+        .config
+        .or_else(Config::default_path)
+        .ok_or("Couldn't find the config, set $AOC_CONFIG or use --config")?;
+    let mut client = Client::from_config(&config)?;
+
+    let verdict = submit_and_record(&mut client, &dir.join("answers"), year, day, part, &answer)?;
+    println!(
+        "{} day {} part {}: {:?} is {}",
+        year, day, part, answer, verdict
+    );
+
+    if verdict != Verdict::Right {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::stand_in;
+    use std::fs;
+    use std::time::Duration;
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    fn client(base_url: &str) -> Client {
+        let config = Config {
+            session: Some("abc".to_string()),
+            base_url: base_url.to_string(),
+            interval: Duration::ZERO,
+        };
+
+        Client::new(config, None)
+    }
+
+    #[test]
+    fn verdicts() {
+        assert_eq!(
+            parse_verdict(&page(
+                "That's the right answer!  You are <span>one gold star</span> closer."
+            )),
+            Verdict::Right
+        );
+        assert_eq!(
+            parse_verdict(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck..."
+            )),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            parse_verdict(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            parse_verdict(&page(
+                "That's not the right answer.  If you're stuck, make sure you're using the \
+                 full input data"
+            )),
+            Verdict::Wrong
+        );
+        assert_eq!(
+            parse_verdict(&page(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 38s left to wait."
+            )),
+            Verdict::RateLimited("38s".to_string())
+        );
+        assert_eq!(
+            parse_verdict(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Verdict::AlreadySolved
+        );
+        assert_eq!(
+            parse_verdict("<p>Something <b>else</b></p>"),
+            Verdict::Unknown("Something else".to_string())
+        );
+    }
+
+    #[test]
+    fn submits_and_records() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("aoc-submit-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("answers");
+
+        let (url, server) = stand_in::serve(vec![
+            stand_in::response(
+                200,
+                &page("That's not the right answer; your answer is too low."),
+            ),
+            stand_in::response(
+                200,
+                &page("You gave an answer too recently; You have 5s left to wait."),
+            ),
+            stand_in::response(200, &page("That's the right answer!")),
+        ]);
+        let mut client = client(&url);
+
+        let submit = |client: &mut Client, answer| {
+            submit_and_record(client, &path, 2019, 4, Part::Two, answer)
+        };
+
+        assert_eq!(submit(&mut client, "12")?, Verdict::TooLow);
+        assert!(submit(&mut client, "12").is_err());
+        assert_eq!(
+            submit(&mut client, "a b&c")?,
+            Verdict::RateLimited("5s".to_string())
+        );
+        assert_eq!(submit(&mut client, "1 2")?, Verdict::Right);
+
+        // Settled by the answers file without asking the site.
+        assert_eq!(submit(&mut client, "1 2")?, Verdict::Right);
+        assert!(submit(&mut client, "34").is_err());
+
+        let answers = Answers::load(&path)?;
+        assert_eq!(answers.get(4, Part::Two), Some("1 2"));
+        assert_eq!(answers.rejection(4, Part::Two, "12"), Some("too low"));
+        assert_eq!(answers.rejection(4, Part::Two, "a b&c"), None);
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("POST /2019/day/4/answer "));
+        assert!(requests[0].contains("\r\nCookie: session=abc\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=12"));
+        assert!(requests[1].ends_with("\r\n\r\nlevel=2&answer=a%20b%26c"));
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn server_errors() {
+        let (url, server) = stand_in::serve(vec![stand_in::response(500, "Oops")]);
+
+        let error = submit(&mut client(&url), 2019, 1, Part::One, "3")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "The server responded with 500: Oops");
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn options() -> Result<(), Box<dyn Error>> {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+        let options = Options::parse("aoc submit", args("-d 3 -p 2 --answer 410"))?;
+        assert_eq!(options.day, Some(3));
+        assert_eq!(options.part, Some(Part::Two));
+        assert_eq!(options.answer, Some("410".to_string()));
+
+        assert!(Options::parse("aoc submit", args("-d 3")).is_err());
+        assert!(Options::parse("aoc submit", args("-p 1")).is_err());
+
+        Ok(())
+    }
+}
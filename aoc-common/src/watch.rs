@@ -0,0 +1,383 @@
+//! Reruns a day whenever its source or input changes.
+//!
+//! Each run is a `cargo run` of the same binary with the results printed as
+//! csv, so edits to the solution are rebuilt before it runs. The results are
+//! compared with the previous run's.
+
+use crate::runner::{self, Outcome, Source};
+use crate::solution::{Day, Part};
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched files are checked.
+const POLL: Duration = Duration::from_millis(500);
+
+/// The day's source file and wherever its input is read from.
+pub fn watched_files(crate_dir: &Path, day: &Day, source: &Source) -> Result<Vec<PathBuf>, String> {
+    let mut files = vec![crate_dir.join("src").join(format!("day{}.rs", day.day))];
+    let embedded = crate_dir.join("res").join(day.day.to_string());
+
+    match source {
+        Source::Embedded => files.push(embedded),
+        Source::Dir(dir) => {
+            files.push(dir.join(day.year.to_string()).join(day.day.to_string()));
+            files.push(embedded);
+        }
+        Source::File(path) => files.push(path.clone()),
+        Source::Stdin => return Err("Can't watch stdin".to_string()),
+    }
+
+    Ok(files)
+}
+
+/// The modification time of each file, `None` for missing files.
+fn modified(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|f| fs::metadata(f).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// The arguments for a run of just this day, with csv output.
+fn run_args(day: &Day, part: Option<Part>, source: &Source) -> Vec<String> {
+    let mut args = vec![
+        "--year".to_string(),
+        day.year.to_string(),
+        "--day".to_string(),
+        day.day.to_string(),
+        "--format".to_string(),
+        "csv".to_string(),
+    ];
+
+    if let Some(part) = part {
+        args.extend(["--part".to_string(), part.to_string()]);
+    }
+
+    match source {
+        Source::Dir(dir) => args.extend(["--inputs".to_string(), dir.display().to_string()]),
+        Source::File(path) => args.extend(["--input".to_string(), path.display().to_string()]),
+        Source::Embedded | Source::Stdin => {}
+    }
+
+    args
+}
+
+/// Splits csv as written by `report::csv` into rows of fields.
+fn parse_csv(csv: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, '\r') => {}
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("Unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+/// Reads the outcomes back from `report::csv` output.
+pub fn outcomes(csv: &str) -> Result<Vec<Outcome>, Box<dyn Error>> {
+    parse_csv(csv)?
+        .into_iter()
+        .skip(1)
+        .map(|fields| {
+            if fields.len() != 7 {
+                return Err(format!("Expected 7 fields, got {:?}", fields).into());
+            }
+
+            let answer = if fields[4].is_empty() {
+                Ok(fields[3].clone())
+            } else {
+                Err(fields[4].clone())
+            };
+
+            Ok(Outcome {
+                year: fields[0].parse()?,
+                day: fields[1].parse()?,
+                part: fields[2].parse()?,
+                answer,
+                elapsed: Duration::from_nanos(fields[5].parse()?),
+                expected: None,
+            })
+        })
+        .collect()
+}
+
+/// How much slower or faster, e.g. `-38%`.
+fn change(previous: Duration, current: Duration) -> String {
+    if previous.is_zero() {
+        return String::new();
+    }
+
+    let ratio = current.as_secs_f64() / previous.as_secs_f64();
+    format!("{:+.0}%", (ratio - 1.0) * 100.0)
+}
+
+/// Lays out the outcomes with how their timing changed since the previous run,
+/// and the previous answer under any answer that changed.
+pub fn diff(previous: &[Outcome], current: &[Outcome]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Year  Day  Part       Time  Change  Answer");
+    let _ = writeln!(out, "----  ---  ----  ---------  ------  ------");
+
+    for outcome in current {
+        let before = previous
+            .iter()
+            .find(|o| (o.year, o.day, o.part) == (outcome.year, outcome.day, outcome.part));
+
+        let prefix = format!(
+            "{:>4}  {:>3}  {:>4}  {:>9}  {:>6}  ",
+            outcome.year,
+            outcome.day,
+            outcome.part,
+            runner::format_elapsed(outcome.elapsed),
+            before.map_or_else(String::new, |b| change(b.elapsed, outcome.elapsed))
+        );
+        runner::row(&mut out, &prefix, &runner::answer_text(outcome));
+
+        if let Some(before) = before.filter(|b| b.answer != outcome.answer) {
+            let prefix = format!("{:width$}was ", "", width = prefix.chars().count());
+            runner::row(&mut out, &prefix, &runner::answer_text(before));
+        }
+    }
+
+    out
+}
+
+/// The arguments for `cargo run` of the binary `name` from `package`, with
+/// the runner's `args`. The manifest is the year's crate, which cargo only
+/// uses to find the workspace, so the binary's package is picked with `-p`.
+fn cargo_args(name: &str, package: &str, crate_dir: &Path, args: &[String]) -> Vec<String> {
+    let manifest = crate_dir.join("Cargo.toml");
+    let mut cargo_args = vec![
+        "run".to_string(),
+        "--quiet".to_string(),
+        "--manifest-path".to_string(),
+        manifest.display().to_string(),
+        "-p".to_string(),
+        package.to_string(),
+        "--bin".to_string(),
+        name.to_string(),
+        "--".to_string(),
+    ];
+    cargo_args.extend(args.iter().cloned());
+    cargo_args
+}
+
+/// Builds and runs the day with `cargo run`, returning `None` if nothing was
+/// printed, e.g. because it didn't compile. Cargo's and the runner's messages
+/// go straight to stderr.
+fn run_once(
+    name: &str,
+    package: &str,
+    crate_dir: &Path,
+    args: &[String],
+) -> Result<Option<Vec<Outcome>>, Box<dyn Error>> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = Command::new(cargo)
+        .args(cargo_args(name, package, crate_dir, args))
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return Ok(None);
+    }
+
+    outcomes(&stdout).map(Some)
+}
+
+/// Runs the day, then again every time one of its files changes. `name` and
+/// `package` are the binary running and the package it's from, `crate_dir` is
+/// the directory of the crate the day is in. Only returns if something goes
+/// wrong.
+pub fn watch(
+    name: &str,
+    package: &str,
+    crate_dir: &Path,
+    day: &Day,
+    part: Option<Part>,
+    source: &Source,
+) -> Result<(), Box<dyn Error>> {
+    let files = watched_files(crate_dir, day, source)?;
+    let args = run_args(day, part, source);
+
+    eprintln!(
+        "Watching {}",
+        files
+            .iter()
+            .map(|f| f.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut previous = Vec::new();
+    let mut seen = modified(&files);
+
+    loop {
+        match run_once(name, package, crate_dir, &args)? {
+            Some(outcomes) => {
+                println!("{}", diff(&previous, &outcomes));
+                previous = outcomes;
+            }
+            None => eprintln!("Nothing ran, waiting for changes\n"),
+        }
+
+        loop {
+            thread::sleep(POLL);
+
+            let now = modified(&files);
+            if now != seen {
+                seen = now;
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report;
+    use crate::solution::{Registry, Solution};
+
+    struct Nothing;
+
+    impl Solution for Nothing {
+        type Input = ();
+        type Answer1 = u8;
+        type Answer2 = u8;
+
+        fn parse(_input: &str) -> Result<Self::Input, Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input) -> Result<Self::Answer1, Box<dyn Error>> {
+            Ok(0)
+        }
+
+        fn part2(_input: &Self::Input) -> Result<Self::Answer2, Box<dyn Error>> {
+            Ok(0)
+        }
+    }
+
+    fn outcome(part: Part, answer: Result<&str, &str>, micros: u64) -> Outcome {
+        Outcome {
+            year: 2019,
+            day: 3,
+            part,
+            answer: answer.map(String::from).map_err(String::from),
+            elapsed: Duration::from_micros(micros),
+            expected: None,
+        }
+    }
+
+    #[test]
+    fn files_and_args() -> Result<(), Box<dyn Error>> {
+        let mut registry = Registry::new();
+        registry.add::<Nothing>(2019, 3, "");
+        let day = registry.get(2019, 3).ok_or("Not registered")?;
+        let dir = Path::new("aoc19");
+
+        assert_eq!(
+            watched_files(dir, day, &Source::Embedded)?,
+            vec![dir.join("src/day3.rs"), dir.join("res/3")]
+        );
+        assert_eq!(
+            watched_files(dir, day, &Source::File("in".into()))?,
+            vec![dir.join("src/day3.rs"), PathBuf::from("in")]
+        );
+        assert!(watched_files(dir, day, &Source::Stdin).is_err());
+
+        assert_eq!(
+            run_args(day, Some(Part::Two), &Source::Dir("inputs".into())).join(" "),
+            "--year 2019 --day 3 --format csv --part 2 --inputs inputs"
+        );
+
+        // A 2018 day run by the 2019 binary builds the binary's package, not
+        // the one the day is in.
+        assert_eq!(
+            cargo_args("aoc19", "aoc19", Path::new("aoc18"), &["--day".to_string()]).join(" "),
+            format!(
+                "run --quiet --manifest-path {} -p aoc19 --bin aoc19 -- --day",
+                Path::new("aoc18").join("Cargo.toml").display()
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_csv() -> Result<(), Box<dyn Error>> {
+        let written = vec![
+            outcome(Part::One, Ok("# \"a\",\nb"), 15),
+            outcome(Part::Two, Err("Not, solved"), 7),
+        ];
+
+        let read = outcomes(&report::csv(&written))?;
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].answer, written[0].answer);
+        assert_eq!(read[1].answer, written[1].answer);
+        assert_eq!(read[1].part, Part::Two);
+        assert_eq!(read[1].elapsed, Duration::from_micros(7));
+
+        assert!(outcomes("header\n1,2\n").is_err());
+        assert!(parse_csv("\"open").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn diffs() {
+        let previous = vec![
+            outcome(Part::One, Ok("0"), 2000),
+            outcome(Part::Two, Ok("30"), 100),
+        ];
+        let current = vec![
+            outcome(Part::One, Ok("6"), 1500),
+            outcome(Part::Two, Ok("30"), 150),
+        ];
+
+        assert_eq!(
+            diff(&previous, &current),
+            "Year  Day  Part       Time  Change  Answer\n\
+             ----  ---  ----  ---------  ------  ------\n\
+             2019    3     1     1.50ms    -25%  6\n\
+             \x20                                   was 0\n\
+             2019    3     2    150.0µs    +50%  30\n"
+        );
+
+        assert!(diff(&[], &current).contains("2019    3     1     1.50ms          6\n"));
+    }
+}
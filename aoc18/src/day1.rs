@@ -1,57 +1,50 @@
+use aoc_common::{parse, Example, Solution};
 use std::collections::HashSet;
+use std::error::Error;
 
-const INPUT: &str = include_str!("../res/1");
+pub const INPUT: &str = include_str!("../res/1");
 
-pub fn solve() {
-    part1();
-    part2();
-}
-
-fn part1() {
-    let freq: i32 = INPUT.lines().map(|r| r.parse::<i32>().unwrap()).sum();
-    println!(" 1 1 The resuling frequency is {}", freq);
-}
-
-fn part2() {
-    let values = INPUT
-        .lines()
-        .map(|l| l.parse::<i32>().unwrap())
-        .collect::<Vec<_>>();
+pub struct Day1;
 
-    let mut seen = HashSet::new();
-    seen.insert(0);
+impl Solution for Day1 {
+    type Input = Vec<i32>;
+    type Answer1 = i32;
+    type Answer2 = i32;
 
-    let mut frequency = 0;
-    let mut result: Option<i32> = None;
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1("+1\n-2\n+3\n+1", "3"),
+        Example::part1("+1\n+1\n-2", "0"),
+        Example::part2("+1\n-1", "0"),
+        Example::part2("+3\n+3\n+4\n-2\n-4", "10"),
+        Example::part2("-6\n+3\n+8\n+5\n-6", "5"),
+    ];
 
-    while result == None {
-        for value in &values {
-            frequency += value;
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(parse::integers(input)?)
+    }
 
-            if seen.contains(&frequency) {
-                result = Some(frequency);
-                break;
-            }
+    fn part1(values: &Self::Input) -> Result<i32, Box<dyn Error>> {
+        Ok(values.iter().sum())
+    }
 
-            seen.insert(frequency);
+    fn part2(values: &Self::Input) -> Result<i32, Box<dyn Error>> {
+        if values.is_empty() {
+            return Err("Couldn't find a duplicate frequency".into());
         }
-    }
 
-    if let Some(freq) = result {
-        println!(" 1 2 The first frequency seen twice is {:?}", freq);
-    } else {
-        eprintln!(" 1 2 Couldn't find a duplicate frequency");
-    }
-}
+        let mut seen = HashSet::new();
+        seen.insert(0);
 
-mod tests {
-    #[bench]
-    fn bench_part1(b: &mut test::Bencher) {
-        b.iter(|| super::part1());
-    }
+        let mut frequency = 0;
 
-    #[bench]
-    fn bench_part2(b: &mut test::Bencher) {
-        b.iter(|| super::part2());
+        loop {
+            for value in values {
+                frequency += value;
+
+                if !seen.insert(frequency) {
+                    return Ok(frequency);
+                }
+            }
+        }
     }
 }
@@ -1,18 +1,15 @@
+use aoc_common::{Example, Solution};
 use std::collections::HashMap;
+use std::error::Error;
 
-const INPUT: &str = include_str!("../res/2");
+pub const INPUT: &str = include_str!("../res/2");
 
-pub fn solve() {
-    part1();
-    part2();
-}
+pub struct Day2;
 
-fn part1() {
+fn checksum(values: &[String]) -> u32 {
     let mut doubles = 0;
     let mut triples = 0;
 
-    let values = INPUT.lines().collect::<Vec<_>>();
-
     // To speed things up I could create a 26 item Vec and convert the
     // character to an index.
     let mut char_counts = HashMap::<char, u32>::new();
@@ -37,12 +34,7 @@ fn part1() {
         };
     }
 
-    println!(
-        " 2 1 There are {} doubles and {} triples, the checksum is {}",
-        doubles,
-        triples,
-        doubles * triples
-    );
+    doubles * triples
 }
 
 fn differences<'a>(left: &'a str, right: &'a str) -> usize {
@@ -60,11 +52,11 @@ fn commonalities(left: &str, right: &str) -> String {
         .collect()
 }
 
-fn find_correct_boxes<'a>(ids: &'a [&str]) -> Option<(&'a str, &'a str)> {
+fn find_correct_boxes(ids: &[String]) -> Option<(&str, &str)> {
     for left in ids {
         for right in ids {
-            if differences(&left, &right) == 1 {
-                return Some((&left, &right));
+            if differences(left, right) == 1 {
+                return Some((left, right));
             }
         }
     }
@@ -72,13 +64,29 @@ fn find_correct_boxes<'a>(ids: &'a [&str]) -> Option<(&'a str, &'a str)> {
     None
 }
 
-fn part2() {
-    let ids = INPUT.lines().collect::<Vec<_>>();
+impl Solution for Day2 {
+    type Input = Vec<String>;
+    type Answer1 = u32;
+    type Answer2 = String;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1(
+            "abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab",
+            "12",
+        ),
+        Example::part2("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz", "fgij"),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(input.lines().map(String::from).collect())
+    }
 
-    let correct_ids = find_correct_boxes(&ids).expect("Couldn't find the correct boxes");
+    fn part1(ids: &Self::Input) -> Result<u32, Box<dyn Error>> {
+        Ok(checksum(ids))
+    }
 
-    println!(
-        " 2 2 The common letters between the correct box IDs are {}",
-        commonalities(correct_ids.0, correct_ids.1),
-    );
+    fn part2(ids: &Self::Input) -> Result<String, Box<dyn Error>> {
+        let correct_ids = find_correct_boxes(ids).ok_or("Couldn't find the correct boxes")?;
+        Ok(commonalities(correct_ids.0, correct_ids.1))
+    }
 }
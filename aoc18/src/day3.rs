@@ -1,16 +1,16 @@
+use aoc_common::{parse, Example, Point2, Solution};
 use std::collections::HashMap;
+use std::error::Error;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("../res/3");
+pub const INPUT: &str = include_str!("../res/3");
 
-#[derive(Eq, Hash, PartialEq)]
-struct Point {
-    x: u16,
-    y: u16,
-}
+type Point = Point2<u16>;
+
+pub struct Day3;
 
 #[derive(Debug)]
-struct Rect {
+pub struct Rect {
     id: u16,
     x: u16,
     y: u16,
@@ -18,24 +18,29 @@ struct Rect {
     h: u16,
 }
 
+/// Parses a claim like `#1 @ 1,3: 4x4`.
 impl FromStr for Rect {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let subs: Vec<&str> = s.split_whitespace().collect();
-        let points: Vec<&str> = subs[2].split(",").collect();
-        let dimensions: Vec<&str> = subs[3].split("x").collect();
-
-        let rect = Rect {
-            id: subs[0][1..].parse().unwrap(),
-            x: points[0].parse().unwrap(),
-            // https://github.com/rust-lang/rfcs/issues/2249
-            y: points[1][..points[1].len() - 1].parse().unwrap(),
-            w: dimensions[0].parse().unwrap(),
-            h: dimensions[1].parse().unwrap(),
-        };
-
-        Ok(rect)
+        let layout = || "Expected a claim like #1 @ 1,3: 4x4".to_string();
+
+        let (id, rest) = s
+            .trim()
+            .strip_prefix('#')
+            .and_then(|s| s.split_once('@'))
+            .ok_or_else(layout)?;
+        let (position, size) = rest.split_once(':').ok_or_else(layout)?;
+        let (x, y) = position.split_once(',').ok_or_else(layout)?;
+        let (w, h) = size.split_once('x').ok_or_else(layout)?;
+
+        Ok(Rect {
+            id: parse::number(id)?,
+            x: parse::number(x)?,
+            y: parse::number(y)?,
+            w: parse::number(w)?,
+            h: parse::number(h)?,
+        })
     }
 }
 
@@ -50,7 +55,7 @@ impl Fabric {
         // nicer.
         for x in r.x..r.x + r.w {
             for y in r.y..r.y + r.h {
-                let p = Point { x, y };
+                let p = Point::new(x, y);
                 *self.claims.entry(p).or_insert(0) += 1;
             }
         }
@@ -63,7 +68,7 @@ impl Fabric {
     fn is_uncontested(&self, rect: &Rect) -> bool {
         for x in rect.x..rect.x + rect.w {
             for y in rect.y..rect.y + rect.h {
-                if self.claims[&Point { x, y }] != 1 {
+                if self.claims[&Point::new(x, y)] != 1 {
                     return false;
                 }
             }
@@ -73,30 +78,46 @@ impl Fabric {
     }
 }
 
-pub fn solve() {
-    let mut fabric = Fabric::default();
+impl Fabric {
+    fn from_claims(rects: &[Rect]) -> Self {
+        let mut fabric = Fabric::default();
+
+        for rect in rects {
+            fabric.add_claim(rect);
+        }
+
+        fabric
+    }
+}
+
+impl Solution for Day3 {
+    type Input = Vec<Rect>;
+    type Answer1 = usize;
+    type Answer2 = u16;
 
-    let rects: Vec<Rect> = INPUT
-        .lines()
-        .map(|l| Rect::from_str(&l))
-        .map(|r| r.unwrap())
-        .collect();
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2", "4"),
+        Example::part2("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2", "3"),
+    ];
 
-    for rect in &rects {
-        fabric.add_claim(rect);
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(parse::lines(input, Rect::from_str)?)
     }
 
-    println!(
-        " 3 1 There are {} square inches of fabric with contested claims",
-        fabric.get_contested_inches_count()
-    );
+    fn part1(rects: &Self::Input) -> Result<usize, Box<dyn Error>> {
+        Ok(Fabric::from_claims(rects).get_contested_inches_count())
+    }
+
+    fn part2(rects: &Self::Input) -> Result<u16, Box<dyn Error>> {
+        let fabric = Fabric::from_claims(rects);
 
-    let id = rects
-        .iter()
-        .filter(|r| fabric.is_uncontested(r))
-        .map(|r| r.id)
-        .next()
-        .expect("Couldn't find an uncontested claim id");
+        let id = rects
+            .iter()
+            .filter(|r| fabric.is_uncontested(r))
+            .map(|r| r.id)
+            .next()
+            .ok_or("Couldn't find an uncontested claim id")?;
 
-    println!(" 3 2 Claim {} is uncontested", id);
+        Ok(id)
+    }
 }
@@ -1,76 +1,87 @@
 //use std::collections::HashMap;
+use aoc_common::{parse, Solution};
+use std::error::Error;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("../res/4");
+pub const INPUT: &str = include_str!("../res/4");
 
-pub fn solve() {
-    part1();
+pub struct Day4;
+
+#[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum EventType {
+    Begin(u16),
+    Sleep,
+    Wake,
 }
 
-fn part1() {
-    #[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
-    enum EventType {
-        Begin(u16),
-        Sleep,
-        Wake,
-    }
+#[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Event {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    event_type: EventType,
+}
 
-    #[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
-    struct Event {
-        year: u16,
-        month: u8,
-        day: u8,
-        hour: u8,
-        minute: u8,
-        event_type: EventType,
-    }
+/// Parses an event like `[1518-11-01 00:05] falls asleep`.
+impl FromStr for Event {
+    type Err = String;
 
-    impl FromStr for Event {
-        type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let layout = || "Expected an event like [1518-11-01 00:05] falls asleep".to_string();
 
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let event_type: EventType;
+        let (timestamp, description) = s
+            .trim()
+            .strip_prefix('[')
+            .and_then(|s| s.split_once("] "))
+            .ok_or_else(layout)?;
+        let (date, time) = timestamp.split_once(' ').ok_or_else(layout)?;
+        let (year, rest) = date.split_once('-').ok_or_else(layout)?;
+        let (month, day) = rest.split_once('-').ok_or_else(layout)?;
+        let (hour, minute) = time.split_once(':').ok_or_else(layout)?;
 
-            if s[19..].find("falls asleep").is_some() {
-                event_type = EventType::Sleep;
-            } else if s[19..].find("wakes up").is_some() {
-                event_type = EventType::Wake;
-            } else {
-                let sub = &s[s.find('#').expect("find #") + 1..];
-                let id: u16 = sub[..sub.find(" ").expect("find id space")]
-                    .parse()
-                    .expect("parse id");
-                event_type = EventType::Begin(id);
+        let event_type = match description {
+            "falls asleep" => EventType::Sleep,
+            "wakes up" => EventType::Wake,
+            _ => {
+                let id = description
+                    .strip_prefix("Guard #")
+                    .and_then(|s| s.strip_suffix(" begins shift"))
+                    .ok_or_else(|| format!("Unknown event {:?}", description))?;
+                EventType::Begin(parse::number(id)?)
             }
+        };
 
-            Ok(Event {
-                year: s[1..5].parse().expect("year"),
-                month: s[6..8].parse().expect("month"),
-                day: s[9..11].parse().expect("day"),
-                hour: s[12..14].parse().expect("hour"),
-                minute: s[15..17].parse().expect("minute"),
-                event_type,
-            })
-        }
+        Ok(Event {
+            year: parse::number(year)?,
+            month: parse::number(month)?,
+            day: parse::number(day)?,
+            hour: parse::number(hour)?,
+            minute: parse::number(minute)?,
+            event_type,
+        })
     }
+}
 
-    let mut events: Vec<_> = INPUT
-        .lines()
-        .map(|l| Event::from_str(&l))
-        .map(|e| e.expect("unwrap event"))
-        .collect();
+impl Solution for Day4 {
+    type Input = Vec<Event>;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-    events.sort();
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        let mut events = parse::lines(input, Event::from_str)?;
 
-    //let mut totals = HashMap::new();
-    /*
-        for event in events {
-            let mut current_id;
-            match event.event_type {
-                EventType::Begin(id) => current_id = id,
-                EventType::Begin => total.entry(current_id).or_insert(0) +=
-            }
-        }
-    */
-    assert_eq!(1, 2);
+        events.sort();
+
+        Ok(events)
+    }
+
+    fn part1(_events: &Self::Input) -> Result<u32, Box<dyn Error>> {
+        Err("Part 1 isn't solved yet".into())
+    }
+
+    fn part2(_events: &Self::Input) -> Result<u32, Box<dyn Error>> {
+        Err("Part 2 isn't solved yet".into())
+    }
 }
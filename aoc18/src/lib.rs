@@ -0,0 +1,32 @@
+use aoc_common::Registry;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+
+pub const YEAR: u16 = 2018;
+
+pub fn register(registry: &mut Registry) {
+    registry
+        .set_dir(YEAR, env!("CARGO_MANIFEST_DIR"))
+        .add::<day1::Day1>(YEAR, 1, day1::INPUT)
+        .add::<day2::Day2>(YEAR, 2, day2::INPUT)
+        .add::<day3::Day3>(YEAR, 3, day3::INPUT)
+        .add::<day4::Day4>(YEAR, 4, day4::INPUT);
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_common::{runner, Registry};
+
+    #[test]
+    fn examples() {
+        let mut registry = Registry::new();
+        super::register(&mut registry);
+
+        if let Err(failures) = runner::check_examples(&registry) {
+            panic!("\n{}", failures);
+        }
+    }
+}
@@ -1,14 +1,8 @@
-#![feature(test)]
-extern crate test;
-
-mod day1;
-mod day2;
-mod day3;
-mod day4;
+use aoc_common::{runner, Registry};
 
 fn main() {
-    day1::solve();
-    day2::solve();
-    day3::solve();
-    day4::solve();
+    let mut registry = Registry::new();
+    aoc18::register(&mut registry);
+
+    runner::main("aoc18", &registry);
 }
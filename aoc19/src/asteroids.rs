@@ -0,0 +1,265 @@
+//! Draws day 10's asteroid field, either as text frames for the terminal or as
+//! an animated SVG, to see what the station can see and the order the laser
+//! vaporizes asteroids in.
+
+use crate::day10::{best_station, Asteroid, Point};
+use aoc_common::{Grid, Point2};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+/// An asteroid field with a station picked.
+pub struct Field {
+    width: usize,
+    height: usize,
+    asteroids: Vec<Asteroid>,
+    station: Asteroid,
+    order: Vec<Point>,
+}
+
+impl Field {
+    /// The field with the station at the asteroid that can see the most others.
+    pub fn new(asteroids: Vec<Asteroid>) -> Result<Self, String> {
+        let (station, _) = best_station(&asteroids).ok_or("There are no asteroids")?;
+        let station = station.pos;
+        Field::with_station(asteroids, station)
+    }
+
+    /// The field with the station at the given asteroid.
+    pub fn with_station(asteroids: Vec<Asteroid>, station: Point) -> Result<Self, String> {
+        let station = asteroids
+            .iter()
+            .find(|a| a.pos == station)
+            .cloned()
+            .ok_or_else(|| format!("There's no asteroid at {},{}", station.x, station.y))?;
+
+        if let Some(a) = asteroids.iter().find(|a| a.pos.x < 0 || a.pos.y < 0) {
+            return Err(format!("{},{} is outside the field", a.pos.x, a.pos.y));
+        }
+
+        let width = asteroids.iter().map(|a| a.pos.x + 1).max().unwrap_or(0) as usize;
+        let height = asteroids.iter().map(|a| a.pos.y + 1).max().unwrap_or(0) as usize;
+        let order = station.vaporization_order(&asteroids);
+
+        Ok(Field {
+            width,
+            height,
+            asteroids,
+            station,
+            order,
+        })
+    }
+
+    pub fn station(&self) -> &Asteroid {
+        &self.station
+    }
+
+    /// The asteroids the station can see before the laser fires.
+    pub fn visible(&self) -> Vec<Point> {
+        self.station.visible(&self.asteroids)
+    }
+
+    /// The other asteroids in the order they're vaporized.
+    pub fn order(&self) -> &[Point] {
+        &self.order
+    }
+
+    /// The asteroids left after the first `vaporized` are destroyed, including
+    /// the station.
+    fn remaining(&self, vaporized: usize) -> Vec<Asteroid> {
+        let gone = &self.order[..vaporized.min(self.order.len())];
+        self.asteroids
+            .iter()
+            .filter(|a| !gone.contains(&a.pos))
+            .cloned()
+            .collect()
+    }
+
+    /// The field after the first `vaporized` asteroids are destroyed. The
+    /// station is `X`, asteroids it can see are `O`, hidden ones are `#` and
+    /// the ones destroyed since the previous frame, `since`, are `*`.
+    pub fn frame(&self, since: usize, vaporized: usize) -> String {
+        let mut grid = Grid::new(self.width, self.height, '.');
+        let at = |p: Point| Point2::new(p.x as usize, p.y as usize);
+
+        let remaining = self.remaining(vaporized);
+        for asteroid in &remaining {
+            grid[at(asteroid.pos)] = '#';
+        }
+        for pos in self.station.visible(&remaining) {
+            grid[at(pos)] = 'O';
+        }
+        for &pos in &self.order[since.min(vaporized)..vaporized.min(self.order.len())] {
+            grid[at(pos)] = '*';
+        }
+        grid[at(self.station.pos)] = 'X';
+
+        grid.render(|&c| c)
+    }
+
+    /// Every frame from before the laser fires until everything else is
+    /// destroyed, `per_frame` asteroids at a time.
+    pub fn frames(&self, per_frame: usize) -> impl Iterator<Item = String> + '_ {
+        let per_frame = per_frame.max(1);
+        let count = self.order.len().div_ceil(per_frame);
+
+        (0..=count).map(move |i| {
+            let vaporized = i * per_frame;
+            self.frame(vaporized.saturating_sub(per_frame), vaporized)
+        })
+    }
+
+    /// An SVG with each grid cell `scale` pixels square. Lines of sight are
+    /// drawn from the station to every asteroid it can see, and the laser
+    /// destroys an asteroid every `step`, revealing those behind it.
+    pub fn svg(&self, scale: usize, step: Duration) -> String {
+        let step = step.as_millis();
+        let scale = scale as f64;
+        let centre = |p: Point| ((p.x as f64 + 0.5) * scale, (p.y as f64 + 0.5) * scale);
+        let when: HashMap<Point, u128> = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| (p, (i + 1) as u128 * step))
+            .collect();
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = self.width as f64 * scale,
+            h = self.height as f64 * scale
+        );
+        let _ = writeln!(out, r#"<rect width="100%" height="100%" fill="black"/>"#);
+
+        // Each asteroid comes into view when the one in front of it is
+        // destroyed, and its line of sight goes when it is.
+        let (sx, sy) = centre(self.station.pos);
+        for line in self.station.lines_of_sight(&self.asteroids).values() {
+            for (i, &pos) in line.iter().enumerate() {
+                let (x, y) = centre(pos);
+                let shown = if i == 0 { 1 } else { 0 };
+                let _ = write!(
+                    out,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="green" stroke-width="{}" opacity="{}">"#,
+                    sx,
+                    sy,
+                    x,
+                    y,
+                    scale / 10.0,
+                    shown
+                );
+                if i > 0 {
+                    let _ = write!(
+                        out,
+                        r#"<set attributeName="opacity" to="1" begin="{}ms"/>"#,
+                        when[&line[i - 1]]
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    r#"<set attributeName="opacity" to="0" begin="{}ms"/></line>"#,
+                    when[&pos]
+                );
+            }
+        }
+
+        for asteroid in &self.asteroids {
+            let (x, y) = centre(asteroid.pos);
+            let (p, r) = (asteroid.pos, scale * 0.35);
+
+            if asteroid.pos == self.station.pos {
+                let _ = writeln!(
+                    out,
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="red"><title>Station at {},{}</title></circle>"#,
+                    x, y, r, p.x, p.y
+                );
+                continue;
+            }
+
+            let index = self.order.iter().position(|&o| o == p).unwrap_or(0) + 1;
+            let _ = writeln!(
+                out,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="white"><title>{},{} is vaporized {}</title><set attributeName="fill" to="orange" begin="{}ms"/><set attributeName="opacity" to="0" begin="{}ms"/></circle>"#,
+                x,
+                y,
+                r,
+                p.x,
+                p.y,
+                ordinal(index),
+                when[&p] - step,
+                when[&p]
+            );
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+/// `1st`, `2nd`, `3rd`, `4th` and so on.
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day10::Day10;
+    use aoc_common::Solution;
+    use std::error::Error;
+
+    static MAP: &str = ".#..#\n.....\n#####\n....#\n...##";
+
+    #[test]
+    fn frames() -> Result<(), Box<dyn Error>> {
+        let field = Field::new(Day10::parse(MAP)?)?;
+        assert_eq!(field.station().pos, Point::new(3, 4));
+
+        let frames: Vec<String> = field.frames(4).collect();
+        assert_eq!(frames.len(), 4);
+        // 1,0 is hidden behind 2,2 until it's vaporized.
+        assert_eq!(frames[0], ".#..O\n.....\nOOOOO\n....O\n...XO\n");
+        assert_eq!(frames[1], ".#..*\n.....\nOOO**\n....*\n...XO\n");
+        assert_eq!(frames[2], ".O...\n.....\n***..\n.....\n...X*\n");
+        assert_eq!(frames[3], ".*...\n.....\n.....\n.....\n...X.\n");
+
+        let field = Field::with_station(Day10::parse(MAP)?, Point::new(4, 0))?;
+        assert_eq!(field.visible().len(), 7);
+        assert_eq!(field.frame(0, 0), ".O..X\n.....\nOOOOO\n....#\n...O#\n");
+        assert!(Field::with_station(Day10::parse(MAP)?, Point::new(0, 0)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn svg() -> Result<(), Box<dyn Error>> {
+        let field = Field::with_station(Day10::parse("###")?, Point::new(0, 0))?;
+        let svg = field.svg(10, Duration::from_millis(500));
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="10""#)
+        );
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert!(svg.contains("<title>Station at 0,0</title>"));
+        assert!(svg.contains("<title>2,0 is vaporized 2nd</title>"));
+        // The far asteroid's line of sight appears when the near one goes.
+        assert!(svg.contains(
+            r#"<line x1="5" y1="5" x2="25" y2="5" stroke="green" stroke-width="1" opacity="0"><set attributeName="opacity" to="1" begin="500ms"/><set attributeName="opacity" to="0" begin="1000ms"/></line>"#
+        ));
+
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(23), "23rd");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+//! Shows day 10's asteroid field: where the station is, what it can see and the
+//! order the laser vaporizes everything else in, as a terminal animation or an
+//! SVG.
+
+use aoc19::asteroids::Field;
+use aoc19::day10::{self, Day10, Point};
+use aoc_common::Solution;
+use std::error::Error;
+use std::thread;
+use std::time::Duration;
+
+static USAGE: &str = "\
+Usage: asteroids [map] [options]
+
+Reads the map from the file given, or uses the day 10 input.
+
+Options:
+    -s, --station <x,y>        Put the station here instead of the best asteroid
+    -a, --animate              Play the vaporization in the terminal
+    -n, --per-frame <count>    Asteroids vaporized per frame, defaults to 1
+    -d, --delay <ms>           Time between frames, defaults to 50
+    -o, --svg <file>           Write an animated SVG of the field
+    -h, --help                 Print this message
+
+The station is drawn as X, asteroids it can see as O, hidden asteroids as #
+and newly vaporized ones as *.";
+
+struct Options {
+    path: Option<String>,
+    station: Option<Point>,
+    animate: bool,
+    per_frame: usize,
+    delay: u64,
+    svg: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            path: None,
+            station: None,
+            animate: false,
+            per_frame: 1,
+            delay: 50,
+            svg: None,
+        }
+    }
+}
+
+fn parse_point(s: &str) -> Result<Point, Box<dyn Error>> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| format!("Expected x,y, got {:?}", s))?;
+    Ok(Point::new(x.trim().parse()?, y.trim().parse()?))
+}
+
+fn parse_args() -> Result<Options, Box<dyn Error>> {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "-s" | "--station" => options.station = Some(parse_point(&value()?)?),
+            "-a" | "--animate" => options.animate = true,
+            "-n" | "--per-frame" => options.per_frame = value()?.parse()?,
+            "-d" | "--delay" => options.delay = value()?.parse()?,
+            "-o" | "--svg" => options.svg = Some(value()?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => {
+                return Err(format!("Unknown option {}\n\n{}", arg, USAGE).into())
+            }
+            _ if options.path.is_none() => options.path = Some(arg),
+            _ => return Err(format!("Unexpected argument {}\n\n{}", arg, USAGE).into()),
+        }
+    }
+
+    Ok(options)
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let options = parse_args()?;
+    let map = match &options.path {
+        Some(path) => {
+            std::fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path, e))?
+        }
+        None => day10::INPUT.to_string(),
+    };
+
+    let asteroids = Day10::parse(&map)?;
+    let field = match options.station {
+        Some(station) => Field::with_station(asteroids, station)?,
+        None => Field::new(asteroids)?,
+    };
+
+    let station = field.station().pos;
+    println!(
+        "Station at {},{} can see {} of the other {} asteroids",
+        station.x,
+        station.y,
+        field.visible().len(),
+        field.order().len()
+    );
+
+    if let Some(pos) = field.order().get(199) {
+        println!("The 200th asteroid vaporized is at {},{}", pos.x, pos.y);
+    }
+
+    if let Some(path) = &options.svg {
+        std::fs::write(path, field.svg(16, Duration::from_millis(100)))
+            .map_err(|e| format!("Couldn't write {}: {}", path, e))?;
+    }
+
+    if !options.animate {
+        print!("{}", field.frame(0, 0));
+        return Ok(());
+    }
+
+    for frame in field.frames(options.per_frame) {
+        // Clears the screen and moves to the top left.
+        print!("\x1b[2J\x1b[H{}", frame);
+        thread::sleep(Duration::from_millis(options.delay));
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("asteroids: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,201 @@
+//! Runs an intcode program from a file.
+//!
+//! Inputs given with `--input` are used first, anything else the program asks
+//! for is read from stdin.
+
+use aoc19::decompile::decompile;
+use aoc19::intcode::{parse_program, Computer, StepResult};
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+
+static USAGE: &str = "\
+Usage: intcode <program> [options]
+
+Options:
+    -i, --input <values>       Comma separated inputs, may be repeated
+    -p, --patch <addr=value>   Store a value before running, may be repeated
+    -a, --ascii                Print outputs as text and read stdin as text
+    -w, --watch <start..end>   Report writes to an address range on stderr
+    -d, --decompile            Print the program as pseudo-code instead
+    -h, --help                 Print this message";
+
+#[derive(Default)]
+struct Options {
+    path: String,
+    inputs: Vec<i64>,
+    patches: Vec<(usize, i64)>,
+    watches: Vec<Range<usize>>,
+    ascii: bool,
+    decompile: bool,
+}
+
+fn parse_patch(s: &str) -> Result<(usize, i64), Box<dyn Error>> {
+    let mut parts = s.splitn(2, '=');
+    let addr = parts.next().unwrap_or_default().trim().parse()?;
+    let value = parts
+        .next()
+        .ok_or_else(|| format!("Expected addr=value, got {:?}", s))?
+        .trim()
+        .parse()?;
+    Ok((addr, value))
+}
+
+fn parse_range(s: &str) -> Result<Range<usize>, Box<dyn Error>> {
+    let mut parts = s.splitn(2, "..");
+    let start: usize = parts.next().unwrap_or_default().trim().parse()?;
+    let end = match parts.next() {
+        Some(end) => end.trim().parse()?,
+        None => start + 1,
+    };
+    Ok(start..end)
+}
+
+fn parse_args() -> Result<Options, Box<dyn Error>> {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "-i" | "--input" => {
+                for input in value()?.split(',') {
+                    let input = input
+                        .trim()
+                        .parse()
+                        .map_err(|e| format!("Couldn't parse input {:?}: {}", input, e))?;
+                    options.inputs.push(input);
+                }
+            }
+            "-p" | "--patch" => options.patches.push(parse_patch(&value()?)?),
+            "-w" | "--watch" => options.watches.push(parse_range(&value()?)?),
+            "-a" | "--ascii" => options.ascii = true,
+            "-d" | "--decompile" => options.decompile = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => {
+                return Err(format!("Unknown option {}\n\n{}", arg, USAGE).into())
+            }
+            _ if options.path.is_empty() => options.path = arg,
+            _ => return Err(format!("Unexpected argument {}\n\n{}", arg, USAGE).into()),
+        }
+    }
+
+    if options.path.is_empty() {
+        return Err(USAGE.into());
+    }
+
+    Ok(options)
+}
+
+/// Reads the next input from stdin, either as integers separated by
+/// whitespace or commas, or in ASCII mode as a line of text.
+fn read_input(
+    cpu: &mut Computer,
+    ascii: bool,
+    pending: &mut Vec<i64>,
+) -> Result<(), Box<dyn Error>> {
+    let stdin = io::stdin();
+
+    while pending.is_empty() {
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Err("The program needs input but stdin is exhausted".into());
+        }
+
+        if ascii {
+            pending.extend(
+                line.trim_end_matches(&['\r', '\n'][..])
+                    .bytes()
+                    .map(i64::from),
+            );
+            pending.push(i64::from(b'\n'));
+        } else {
+            for token in line.split(|c: char| c == ',' || c.is_whitespace()) {
+                if !token.is_empty() {
+                    let value = token
+                        .parse()
+                        .map_err(|e| format!("Couldn't parse input {:?}: {}", token, e))?;
+                    pending.push(value);
+                }
+            }
+        }
+
+        pending.reverse();
+    }
+
+    while let Some(value) = pending.pop() {
+        cpu.add_input(value);
+    }
+
+    Ok(())
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let options = parse_args()?;
+    let source = std::fs::read_to_string(&options.path)
+        .map_err(|e| format!("Couldn't read {}: {}", options.path, e))?;
+    let program = parse_program(&source)?;
+
+    if options.decompile {
+        print!("{}", decompile(&program));
+        return Ok(());
+    }
+
+    let mut cpu = Computer::new(&program);
+
+    for (addr, value) in &options.patches {
+        cpu.store(*addr, *value)?;
+    }
+
+    for range in &options.watches {
+        cpu.watch(range.clone());
+    }
+
+    for input in &options.inputs {
+        cpu.add_input(*input);
+    }
+
+    let stdout = io::stdout();
+    let mut pending = Vec::new();
+
+    loop {
+        let result = cpu.run();
+
+        for write in cpu.take_writes() {
+            eprintln!(
+                "pc {}: [{}] {} -> {}",
+                write.pc, write.addr, write.old, write.new
+            );
+        }
+
+        let result = result.map_err(|e| format!("{} at pc {}", e, cpu.pc()))?;
+
+        match result {
+            StepResult::OutputAvailable(value) => {
+                let mut out = stdout.lock();
+                match value {
+                    0..=127 if options.ascii => write!(out, "{}", value as u8 as char)?,
+                    _ => writeln!(out, "{}", value)?,
+                }
+            }
+            StepResult::NeedInput => {
+                stdout.lock().flush()?;
+                read_input(&mut cpu, options.ascii, &mut pending)?;
+            }
+            StepResult::Finished => break,
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("intcode: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,180 @@
+//! Decodes a Space Image Format file, prints the composited image and exports
+//! it along with each layer as image files. Can also encode images, to make
+//! test inputs for day 8.
+
+use aoc19::sif::{self, Image};
+use aoc_common::image::Format;
+use aoc_common::ocr;
+use std::error::Error;
+use std::path::PathBuf;
+
+static USAGE: &str = "\
+Usage: sif <image> [options]
+       sif encode <art> [options]
+       sif encode --text <letters> [options]
+
+Decodes an image, or encodes ASCII art where '#' is white, ' ' is black and
+'.' is transparent.
+
+Options:
+    -W, --width <pixels>       The image's width, defaults to 25
+    -H, --height <pixels>      The image's height, defaults to 6
+    -c, --checksum             Print the image's checksum instead of the image
+    -o, --output <dir>         Where to write the composite and layers
+    -f, --format <format>      pgm, ppm or png, defaults to png
+    -s, --scale <factor>       Draw each pixel as a square this big, defaults to 10
+
+Encoding options:
+    -t, --text <letters>       Encode the letters in the 6 pixel high font
+    -l, --layers <count>       Spread the pixels over this many layers, defaults to 1
+    --seed <number>            Seeds where the pixels are spread, defaults to 0
+
+    -h, --help                 Print this message";
+
+struct Options {
+    encode: bool,
+    path: String,
+    text: Option<String>,
+    width: usize,
+    height: usize,
+    checksum: bool,
+    output: Option<PathBuf>,
+    format: Format,
+    scale: usize,
+    layers: usize,
+    seed: i64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            encode: false,
+            path: String::new(),
+            text: None,
+            width: 25,
+            height: 6,
+            checksum: false,
+            output: None,
+            format: Format::Png,
+            scale: 10,
+            layers: 1,
+            seed: 0,
+        }
+    }
+}
+
+fn parse_size(arg: &str, value: &str) -> Result<usize, Box<dyn Error>> {
+    match value.parse() {
+        Ok(0) | Err(_) => Err(format!("{} expects a positive number, got {:?}", arg, value).into()),
+        Ok(size) => Ok(size),
+    }
+}
+
+fn parse_args() -> Result<Options, Box<dyn Error>> {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1).peekable();
+
+    if args.peek().map(String::as_str) == Some("encode") {
+        options.encode = true;
+        args.next();
+    }
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "-W" | "--width" => options.width = parse_size(&arg, &value()?)?,
+            "-H" | "--height" => options.height = parse_size(&arg, &value()?)?,
+            "-c" | "--checksum" => options.checksum = true,
+            "-o" | "--output" => options.output = Some(value()?.into()),
+            "-f" | "--format" => options.format = value()?.parse()?,
+            "-s" | "--scale" => options.scale = parse_size(&arg, &value()?)?,
+            "-t" | "--text" if options.encode => options.text = Some(value()?),
+            "-l" | "--layers" if options.encode => options.layers = parse_size(&arg, &value()?)?,
+            "--seed" if options.encode => {
+                let seed = value()?;
+                options.seed = seed
+                    .parse()
+                    .map_err(|e| format!("Couldn't parse seed {:?}: {}", seed, e))?;
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => {
+                return Err(format!("Unknown option {}\n\n{}", arg, USAGE).into())
+            }
+            _ if options.path.is_empty() => options.path = arg,
+            _ => return Err(format!("Unexpected argument {}\n\n{}", arg, USAGE).into()),
+        }
+    }
+
+    if options.path.is_empty() == options.text.is_none() {
+        return Err(USAGE.into());
+    }
+
+    Ok(options)
+}
+
+/// The image to encode, drawn from the text or read from the art file.
+fn encoded(options: &Options) -> Result<Image, Box<dyn Error>> {
+    let pixels = match &options.text {
+        Some(text) => sif::from_lit(&ocr::render(text)?),
+        None => {
+            let art = std::fs::read_to_string(&options.path)
+                .map_err(|e| format!("Couldn't read {}: {}", options.path, e))?;
+            sif::parse_art(&art).map_err(|e| format!("{}: {}", options.path, e))?
+        }
+    };
+
+    Ok(Image::layered(&pixels, options.layers, options.seed)?)
+}
+
+fn decoded(options: &Options) -> Result<Image, Box<dyn Error>> {
+    let data = std::fs::read_to_string(&options.path)
+        .map_err(|e| format!("Couldn't read {}: {}", options.path, e))?;
+
+    Ok(Image::decode(&data, options.width, options.height)
+        .map_err(|e| format!("{}: {}", options.path, e))?)
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let options = parse_args()?;
+
+    let image = if options.encode {
+        let image = encoded(&options)?;
+        eprintln!(
+            "{}x{}, {} layers",
+            image.width(),
+            image.height(),
+            image.layers().len()
+        );
+        println!("{}", image.encode());
+        image
+    } else {
+        decoded(&options)?
+    };
+
+    // When encoding stdout has the encoded image, so anything else goes to
+    // stderr.
+    match (options.encode, options.checksum) {
+        (true, true) => eprintln!("Checksum {}", image.checksum()),
+        (false, true) => println!("{}", image.checksum()),
+        (false, false) => print!("{}", sif::render(&image.composite())),
+        (true, false) => {}
+    }
+
+    if let Some(dir) = &options.output {
+        let paths = image.export(dir, options.format, options.scale)?;
+        eprintln!("Wrote {} images to {}", paths.len(), dir.display());
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("sif: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -1,24 +1,14 @@
-static INPUT: &str = include_str!("../res/1");
+use aoc_common::{parse, Example, Solution};
+use std::error::Error;
 
-pub fn solve() {
-    part1();
-    part2();
-}
+pub static INPUT: &str = include_str!("../res/1");
+
+pub struct Day1;
 
 fn fuel_required_for_module(mass: i32) -> i32 {
     mass / 3 - 2
 }
 
-fn part1() {
-    let module_fuel: i32 = INPUT
-        .lines()
-        .map(|l| l.parse::<i32>().unwrap())
-        .map(fuel_required_for_module)
-        .sum();
-
-    println!("1.1 {}", module_fuel);
-}
-
 fn fuel_required_for_module_and_its_fuel(mass: i32) -> i32 {
     let mut total = fuel_required_for_module(mass);
     let mut current = total;
@@ -33,13 +23,35 @@ fn fuel_required_for_module_and_its_fuel(mass: i32) -> i32 {
     }
 }
 
-fn part2() {
-    // This could probably be done more succinctly with try_fold
-    let total_fuel: i32 = INPUT
-        .lines()
-        .map(|l| l.parse::<i32>().unwrap())
-        .map(fuel_required_for_module_and_its_fuel)
-        .sum();
+impl Solution for Day1 {
+    type Input = Vec<i32>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1("12", "2"),
+        Example::part1("14", "2"),
+        Example::part1("1969", "654"),
+        Example::part1("100756", "33583"),
+        Example::part2("14", "2"),
+        Example::part2("1969", "966"),
+        Example::part2("100756", "50346"),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        Ok(parse::integers(input)?)
+    }
+
+    fn part1(masses: &Self::Input) -> Result<i32, Box<dyn Error>> {
+        Ok(masses.iter().copied().map(fuel_required_for_module).sum())
+    }
 
-    println!("1.2 {}", total_fuel);
+    fn part2(masses: &Self::Input) -> Result<i32, Box<dyn Error>> {
+        // This could probably be done more succinctly with try_fold
+        Ok(masses
+            .iter()
+            .copied()
+            .map(fuel_required_for_module_and_its_fuel)
+            .sum())
+    }
 }
@@ -1,108 +1,102 @@
-use std::collections::HashSet;
-use std::convert::From;
+use aoc_common::{parse, Example, Point2, Solution};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::error::Error;
 
-static INPUT: &str = include_str!("../res/10");
+pub static INPUT: &str = include_str!("../res/10");
 
-#[derive(Clone, Debug, PartialEq)]
-struct Point {
-    x: f64,
-    y: f64,
-}
+pub struct Day10;
+
+pub type Point = Point2<i64>;
 
-impl Point {
-    fn angle_to(&self, other: &Point) -> f64 {
-        let m1 = self.y / self.x;
-        let m2 = other.y / other.x;
-        m1.atan2(m2)
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
     }
 }
 
-#[derive(Clone, Debug)]
-struct Asteroid {
-    pos: Point,
-    angles_observed: Vec<f64>,
+/// The direction from one point to another as the smallest whole step, so
+/// asteroids on the same line of sight have the same direction.
+pub fn direction(from: Point, to: Point) -> Point {
+    let delta = to - from;
+    let divisor = gcd(delta.x, delta.y).max(1);
+    Point::new(delta.x / divisor, delta.y / divisor)
 }
 
-impl Asteroid {
-    pub fn observe(&mut self, pos: &Point) {
-        if self.pos == *pos {
-            return;
-        }
+/// Orders directions clockwise starting from straight up, without any floating
+/// point. Directions are first split into the right half, including up, and
+/// the left half, including down, then ordered within a half by which side of
+/// each other they're on.
+pub fn clockwise(a: Point, b: Point) -> Ordering {
+    let left = |p: Point| p.x < 0 || (p.x == 0 && p.y > 0);
+    let cross = a.x * b.y - a.y * b.x;
 
-        let angle = self.pos.angle_to(&pos);
-        if !self.angles_observed.contains(&angle) {
-            self.angles_observed.push(angle);
-        } else {
-            //            println!("Already contains {}", angle)
-        }
-    }
+    left(a).cmp(&left(b)).then(0.cmp(&cross))
 }
 
-pub fn solve() {
-    println!("10.1: {}", part1(INPUT));
+#[derive(Clone, Debug)]
+pub struct Asteroid {
+    pub pos: Point,
 }
 
-pub fn part1(input: &str) -> usize {
-    let mut asteroids = Vec::new();
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, ch) in line.chars().enumerate() {
-            if ch == '#' {
-                let pos = Point {
-                    x: x as f64,
-                    y: y as f64,
-                };
-
-                asteroids.push(Asteroid {
-                    pos,
-                    angles_observed: Vec::new(),
-                });
-            }
+impl Asteroid {
+    /// The other asteroids grouped by their direction from this one, each group
+    /// nearest first.
+    pub fn lines_of_sight(&self, asteroids: &[Asteroid]) -> BTreeMap<Point, Vec<Point>> {
+        let mut lines = BTreeMap::new();
+
+        for other in asteroids.iter().filter(|a| a.pos != self.pos) {
+            lines
+                .entry(direction(self.pos, other.pos))
+                .or_insert_with(Vec::new)
+                .push(other.pos);
         }
-    }
-
-    let other_asteroids = asteroids.clone();
 
-    for mut origin in &mut asteroids {
-        for other in &other_asteroids {
-            origin.observe(&other.pos);
+        for line in lines.values_mut() {
+            line.sort_by_key(|p: &Point| p.manhattan_distance(self.pos));
         }
-    }
-
-    let best = asteroids
-        .iter()
-        .max_by_key(|x| x.angles_observed.len())
-        .unwrap();
 
-    let hmm = asteroids
-        .iter()
-        .find(|a| a.pos.x == 11.0 && a.pos.y == 13.0)
-        .unwrap();
+        lines
+    }
 
-    println!("best = {:?}", best.pos);
-    println!("hmm = {}... {:?}", hmm.angles_observed.len(), hmm);
+    /// The asteroids that aren't hidden behind another.
+    pub fn visible(&self, asteroids: &[Asteroid]) -> Vec<Point> {
+        self.lines_of_sight(asteroids)
+            .values()
+            .map(|line| line[0])
+            .collect()
+    }
 
-    best.angles_observed.len()
-}
+    /// The order a laser starting pointing up and rotating clockwise destroys
+    /// the other asteroids in, one per line of sight per rotation.
+    pub fn vaporization_order(&self, asteroids: &[Asteroid]) -> Vec<Point> {
+        let mut lines: Vec<(Point, Vec<Point>)> =
+            self.lines_of_sight(asteroids).into_iter().collect();
+        lines.sort_by(|(a, _), (b, _)| clockwise(*a, *b));
 
-pub fn part2() {}
+        let mut order = Vec::with_capacity(asteroids.len());
+        let mut rotation = 0;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        while order.len() + 1 < asteroids.len() {
+            order.extend(lines.iter().filter_map(|(_, line)| line.get(rotation)));
+            rotation += 1;
+        }
 
-    #[test]
-    fn angles() {
-        assert_eq!(
-            Point { x: 0.0, y: 10.0 }.angle_to(&Point { x: 10.0, y: 20.0 }),
-            45.0
-        );
+        order
     }
+}
 
-    #[test]
-    fn example() {
-        let input = "
+/// The asteroid that can see the most others, and how many it can see.
+pub fn best_station(asteroids: &[Asteroid]) -> Option<(&Asteroid, usize)> {
+    asteroids
+        .iter()
+        .map(|a| (a, a.visible(asteroids).len()))
+        .max_by_key(|(_, visible)| *visible)
+}
+
+static LARGE_EXAMPLE: &str = "\
 .#..##.###...#######
 ##.############..##.
 .#.######.########.#
@@ -124,6 +118,106 @@ mod tests {
 #.#.#.#####.####.###
 ###.##.####.##.#..##";
 
-        assert_eq!(part1(input), 210);
+impl Solution for Day10 {
+    type Input = Vec<Asteroid>;
+    type Answer1 = usize;
+    type Answer2 = i64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1(".#..#\n.....\n#####\n....#\n...##", "8"),
+        Example::part1(LARGE_EXAMPLE, "210"),
+        Example::part2(LARGE_EXAMPLE, "802"),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        let map = parse::char_map(input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+
+        Ok(map
+            .iter()
+            .filter(|(_, asteroid)| **asteroid)
+            .map(|(pos, _)| Asteroid {
+                pos: Point::new(pos.x as i64, pos.y as i64),
+            })
+            .collect())
+    }
+
+    fn part1(asteroids: &Self::Input) -> Result<usize, Box<dyn Error>> {
+        let (_, visible) = best_station(asteroids).ok_or("There are no asteroids")?;
+        Ok(visible)
+    }
+
+    fn part2(asteroids: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        let (station, _) = best_station(asteroids).ok_or("There are no asteroids")?;
+        let order = station.vaporization_order(asteroids);
+        let pos = order
+            .get(199)
+            .ok_or_else(|| format!("Only {} asteroids are vaporized, not 200", order.len()))?;
+
+        Ok(pos.x * 100 + pos.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directions() {
+        assert_eq!(
+            direction(Point::new(0, 10), Point::new(10, 20)),
+            Point::new(1, 1)
+        );
+        assert_eq!(
+            direction(Point::new(4, 4), Point::new(-2, 0)),
+            Point::new(-3, -2)
+        );
+        assert_eq!(
+            direction(Point::new(1, 1), Point::new(1, 7)),
+            Point::new(0, 1)
+        );
+
+        let mut directions = vec![
+            Point::new(-1, -1),
+            Point::new(0, 1),
+            Point::new(1, 0),
+            Point::new(-1, 0),
+            Point::new(1, -2),
+            Point::new(0, -1),
+            Point::new(1, 1),
+        ];
+        directions.sort_by(|a, b| clockwise(*a, *b));
+
+        assert_eq!(
+            directions,
+            vec![
+                Point::new(0, -1),
+                Point::new(1, -2),
+                Point::new(1, 0),
+                Point::new(1, 1),
+                Point::new(0, 1),
+                Point::new(-1, 0),
+                Point::new(-1, -1),
+            ]
+        );
+    }
+
+    #[test]
+    fn vaporization() -> Result<(), Box<dyn Error>> {
+        let asteroids = Day10::parse(LARGE_EXAMPLE)?;
+        let (station, visible) = best_station(&asteroids).ok_or("No station")?;
+        assert_eq!((station.pos, visible), (Point::new(11, 13), 210));
+
+        let order = station.vaporization_order(&asteroids);
+        assert_eq!(order.len(), asteroids.len() - 1);
+        assert_eq!(order[0], Point::new(11, 12));
+        assert_eq!(order[1], Point::new(12, 1));
+        assert_eq!(order[199], Point::new(8, 2));
+        assert_eq!(order[298], Point::new(11, 1));
+
+        Ok(())
     }
 }
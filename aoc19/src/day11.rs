@@ -0,0 +1,175 @@
+use crate::intcode::{parse_program, Computer, StepResult};
+use aoc_common::{ocr, Direction, InfiniteGrid, Point2, Solution};
+use std::error::Error;
+
+pub static INPUT: &str = include_str!("../res/11");
+
+pub struct Day11;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Colour {
+    Black,
+    White,
+}
+
+impl Colour {
+    fn from_output(value: i64) -> Result<Self, Box<dyn Error>> {
+        match value {
+            0 => Ok(Colour::Black),
+            1 => Ok(Colour::White),
+            _ => Err(format!("Unknown colour: {}", value).into()),
+        }
+    }
+}
+
+fn apply_turn(dir: Direction, value: i64) -> Result<Direction, Box<dyn Error>> {
+    match value {
+        0 => Ok(dir.turn_left()),
+        1 => Ok(dir.turn_right()),
+        _ => Err(format!("Unknown turn: {}", value).into()),
+    }
+}
+
+/// Panels the robot hasn't painted are black, apart from the one it starts on.
+struct Hull {
+    start: Colour,
+    painted: InfiniteGrid<Colour>,
+}
+
+impl Hull {
+    fn colour(&self, pos: Point2<i64>) -> Colour {
+        if !self.painted.is_set(pos) && pos == Point2::default() {
+            self.start
+        } else {
+            *self.painted.get(pos)
+        }
+    }
+
+    /// Draws white panels as `#`, with y increasing downwards.
+    fn render(&self) -> String {
+        self.painted.render(|colour| match colour {
+            Colour::White => '#',
+            Colour::Black => ' ',
+        })
+    }
+
+    /// The letters painted in white.
+    fn registration(&self) -> Result<String, String> {
+        ocr::recognise_art(&self.render())
+    }
+}
+
+fn paint(program: &[i64], start: Colour) -> Result<Hull, Box<dyn Error>> {
+    let mut cpu = Computer::new(program);
+    let mut hull = Hull {
+        start,
+        painted: InfiniteGrid::new(Colour::Black),
+    };
+
+    let mut pos = Point2::default();
+    let mut dir = Direction::Up;
+
+    loop {
+        match cpu.run()? {
+            StepResult::NeedInput => {
+                let input = match hull.colour(pos) {
+                    Colour::Black => 0,
+                    Colour::White => 1,
+                };
+                cpu.add_input(input);
+            }
+            StepResult::OutputAvailable(colour) => {
+                let turn = match cpu.run()? {
+                    StepResult::OutputAvailable(turn) => turn,
+                    output => return Err(format!("Expected a turn, got {:?}", output).into()),
+                };
+
+                hull.painted.set(pos, Colour::from_output(colour)?);
+                dir = apply_turn(dir, turn)?;
+                pos = pos.step(dir);
+            }
+            StepResult::Finished => return Ok(hull),
+        }
+    }
+}
+
+impl Solution for Day11 {
+    type Input = Vec<i64>;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        if input.trim().is_empty() {
+            return Err("The input is empty, it needs a puzzle input".into());
+        }
+
+        parse_program(input)
+    }
+
+    fn part1(program: &Self::Input) -> Result<usize, Box<dyn Error>> {
+        Ok(paint(program, Colour::Black)?.painted.len())
+    }
+
+    fn part2(program: &Self::Input) -> Result<String, Box<dyn Error>> {
+        Ok(paint(program, Colour::White)?.registration()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() -> Result<(), Box<dyn Error>> {
+        // Reads a colour before replaying each of the example's outputs.
+        let outputs = [(1, 0), (0, 0), (1, 0), (1, 0), (0, 1), (1, 0), (1, 0)];
+
+        let mut program = Vec::new();
+        for (colour, turn) in &outputs {
+            program.extend(&[3, 100, 104, *colour, 104, *turn]);
+        }
+        program.push(99);
+
+        let hull = paint(&program, Colour::Black)?;
+        assert_eq!(hull.painted.len(), 6);
+        assert_eq!(hull.render(), "  #\n  #\n## \n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_panel_colours() -> Result<(), Box<dyn Error>> {
+        // Repaints the panel it's on with the colour it read, then turns right,
+        // for one lap of a 2x2 square and one more step.
+        let mut program = Vec::new();
+        for _ in 0..5 {
+            program.extend(&[3, 100, 4, 100, 104, 1]);
+        }
+        program.push(99);
+
+        let hull = paint(&program, Colour::White)?;
+        assert_eq!(hull.painted.len(), 4);
+        assert_eq!(hull.colour(Point2::new(0, 0)), Colour::White);
+        assert_eq!(hull.colour(Point2::new(1, 0)), Colour::Black);
+        assert_eq!(hull.render(), "#\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn registration() -> Result<(), Box<dyn Error>> {
+        let mut hull = Hull {
+            start: Colour::White,
+            painted: InfiniteGrid::new(Colour::Black),
+        };
+        for (pos, &lit) in ocr::render("HI")?.iter() {
+            let colour = if lit { Colour::White } else { Colour::Black };
+            hull.painted
+                .set(Point2::new(pos.x as i64 + 1, pos.y as i64), colour);
+        }
+
+        assert_eq!(hull.registration()?, "HI");
+
+        Ok(())
+    }
+}
@@ -1,51 +1,47 @@
-use crate::intcode::{Computer, Mode, StepResult};
+use crate::intcode::{parse_program, Computer, Mode, StepResult};
+use aoc_common::Solution;
+use std::error::Error;
 
-static INPUT: &str = include_str!("../res/2");
+pub static INPUT: &str = include_str!("../res/2");
 
-pub fn solve() {
-    part1();
-    part2();
-}
+pub struct Day2;
 
-fn part1() {
-    let program: Vec<i64> = INPUT
-        .split(',')
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
+impl Solution for Day2 {
+    type Input = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    let mut cpu = Computer::new(&program);
-    cpu.store(1, 12);
-    cpu.store(2, 12);
-    cpu.run().unwrap();
-    let result = cpu.load_value(&Mode::Immediate, 0);
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        parse_program(input)
+    }
 
-    println!("2.1 {}", result);
-}
+    fn part1(program: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        let mut cpu = Computer::new(program);
+        cpu.store(1, 12)?;
+        cpu.store(2, 12)?;
+        cpu.run()?;
+        Ok(cpu.load_value(&Mode::Immediate, 0)?)
+    }
 
-fn part2() {
-    let program: Vec<i64> = INPUT
-        .split(',')
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
-
-    let desired_result = 19_690_720;
-
-    for noun in 0..100 {
-        for verb in 0..100 {
-            let mut cpu = Computer::new(&program);
-            cpu.store(1, noun);
-            cpu.store(2, verb);
-            match cpu.run().unwrap() {
-                StepResult::Finished => {
-                    if cpu.load_value(&Mode::Immediate, 0) == desired_result {
-                        println!("2.2 {}", 100 * noun + verb);
-                        return;
+    fn part2(program: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        let desired_result = 19_690_720;
+
+        for noun in 0..100 {
+            for verb in 0..100 {
+                let mut cpu = Computer::new(program);
+                cpu.store(1, noun)?;
+                cpu.store(2, verb)?;
+                match cpu.run()? {
+                    StepResult::Finished => {
+                        if cpu.load_value(&Mode::Immediate, 0)? == desired_result {
+                            return Ok(100 * noun + verb);
+                        }
                     }
+                    output => return Err(format!("Unexpected output: {:?}", output).into()),
                 }
-                output => panic!("Unexpected output: {:?}", output),
             }
         }
-    }
 
-    println!("No noun and verb combination yields {}", desired_result);
+        Err(format!("No noun and verb combination yields {}", desired_result).into())
+    }
 }
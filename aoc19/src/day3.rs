@@ -1,220 +1,118 @@
-static INPUT: &str = include_str!("../res/3");
+use aoc_common::segments::{self, Intersection, Point, Wire};
+use aoc_common::{parse, Direction, Example, Solution};
+use std::convert::TryFrom;
+use std::error::Error;
 
-// TODO: See if I can combine Move/Line in one pass during parsing
+pub static INPUT: &str = include_str!("../res/3");
 
-enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+pub struct Day3;
 
 struct Move {
-    dir: Dir,
-    length: i16,
-}
-
-impl From<&str> for Move {
-    fn from(input: &str) -> Self {
-        let length = input[1..].parse::<i16>().unwrap();
-        let dir = match input.chars().next() {
-            Some('U') => Dir::Up,
-            Some('D') => Dir::Down,
-            Some('L') => Dir::Left,
-            Some('R') => Dir::Right,
-            ch => panic!("Unsupported direction: {:?}", ch),
-        };
-
-        Move { dir, length }
-    }
+    dir: Direction,
+    length: i64,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-struct Point {
-    x: i16,
-    y: i16,
-}
-
-impl Point {
-    fn manhattan_distance(&self) -> i16 {
-        self.x.abs() + self.y.abs()
-    }
-}
-
-struct Line {
-    from: Point,
-    to: Point,
-}
-
-impl Line {
-    fn point_intersecting(&self, other: &Line) -> Option<Point> {
-        if !self.is_perpendicular_to(other) {
-            return None;
-        }
-
-        let vline: &Line;
-        let hline: &Line;
-
-        if self.is_vertical() {
-            vline = self;
-            hline = other;
-        } else {
-            vline = other;
-            hline = self;
-        }
-
-        if vline.from.x > hline.from.x && vline.from.x > hline.to.x {
-            return None;
-        }
-
-        if vline.from.x < hline.from.x && vline.from.x < hline.to.x {
-            return None;
-        }
-
-        if hline.from.y > vline.from.y && hline.from.y > vline.to.y {
-            return None;
-        }
-
-        if hline.from.y < vline.from.y && hline.from.y < vline.to.y {
-            return None;
-        }
-
-        let point = Point {
-            x: vline.from.x,
-            y: hline.from.y,
-        };
-
-        Some(point)
-    }
-
-    fn steps_to_intersect(&self, point: &Point) -> Option<i16> {
-        if self.is_vertical() {
-            if self.from.x != point.x {
-                return None;
-            }
-
-            if self.from.y < point.y && self.to.y < point.y {
-                return None;
-            }
-
-            if self.from.y > point.y && self.to.y > point.y {
-                return None;
-            }
-
-            return Some((self.from.y - point.y).abs());
-        }
-
-        if self.from.y != point.y {
-            return None;
-        }
-
-        if self.from.x < point.x && self.to.x < point.x {
-            return None;
-        }
-
-        if self.from.x > point.x && self.to.x > point.x {
-            return None;
-        }
+/// Parses a move like `R75`.
+impl TryFrom<&str> for Move {
+    type Error = String;
 
-        Some((self.from.x - point.x).abs())
-    }
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let mut chars = input.trim().chars();
+        let dir = chars
+            .next()
+            .ok_or_else(|| "Expected a move like R75, got nothing".to_string())
+            .and_then(Direction::try_from)?;
+        let length = parse::number(chars.as_str())?;
 
-    #[inline]
-    fn is_perpendicular_to(&self, other: &Line) -> bool {
-        self.is_vertical() != other.is_vertical()
-    }
-
-    #[inline]
-    fn is_vertical(&self) -> bool {
-        self.from.x == self.to.x
-    }
-
-    #[inline]
-    fn length(&self) -> i16 {
-        if self.is_vertical() {
-            self.from.y.max(self.to.y) - self.from.y.min(self.to.y)
-        } else {
-            self.from.x.max(self.to.x) - self.from.x.min(self.to.x)
-        }
+        Ok(Move { dir, length })
     }
 }
 
-fn create_lines_from_moves(moves: &[Move]) -> Vec<Line> {
-    let mut lines = Vec::new();
-    lines.reserve(moves.len());
-
-    let mut position: Point;
-    let mut last_position = Point::default();
-
-    for mv in moves {
-        position = last_position;
-
-        match mv.dir {
-            Dir::Up => position.y -= mv.length,
-            Dir::Down => position.y += mv.length,
-            Dir::Left => position.x -= mv.length,
-            Dir::Right => position.x += mv.length,
-        }
-
-        lines.push(Line {
-            from: last_position,
-            to: position,
-        });
-
-        last_position = position;
+/// The points of an intersection which could be closest to the central port,
+/// or fewest steps along both wires from it: the ends, since the steps change
+/// steadily along it, and the point nearest the port. The port itself doesn't
+/// count, so if it's one of those its neighbours are used instead.
+fn candidates(intersection: &Intersection) -> Vec<Point> {
+    let at = intersection.at;
+    let port = Point::default();
+    let mut points = vec![at.from, at.to, at.nearest(port)];
+
+    if at.contains(port) {
+        points.extend(port.neighbours().iter().filter(|&&p| at.contains(p)));
     }
 
-    lines
+    points.retain(|&p| p != port);
+    points
 }
 
-fn find_path_intersections(wire1: &[Line], wire2: &[Line]) -> Vec<Point> {
-    let mut intersections = Vec::new();
-    for line1 in wire1 {
-        for line2 in wire2 {
-            if let Some(point) = line1.point_intersecting(line2) {
-                intersections.push(point);
-            }
-        }
-    }
-
-    intersections
+/// Where different wires meet, not counting the central port they start from,
+/// with the total steps along both wires to get there.
+fn crossings(wires: &[Wire]) -> impl Iterator<Item = (Point, i64)> {
+    segments::intersections(wires)
+        .into_iter()
+        .filter(Intersection::is_between_wires)
+        .flat_map(|i| {
+            candidates(&i)
+                .into_iter()
+                .map(move |p| (p, i.a.steps_to(p) + i.b.steps_to(p)))
+        })
 }
 
-fn count_steps_to_intersection(path: &[Line], point: &Point) -> i16 {
-    let mut total: i16 = 0;
-
-    for line in path {
-        if let Some(steps) = line.steps_to_intersect(point) {
-            return total + steps;
+impl Solution for Day3 {
+    type Input = Vec<Wire>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1("R8,U5,L5,D3\nU7,R6,D4,L4", "6"),
+        Example::part1(
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83",
+            "159",
+        ),
+        Example::part1(
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+            "135",
+        ),
+        Example::part2("R8,U5,L5,D3\nU7,R6,D4,L4", "30"),
+        Example::part2(
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83",
+            "610",
+        ),
+        Example::part2(
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+            "410",
+        ),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        let wires = parse::lines(input, |l| {
+            let moves = l
+                .split(',')
+                .map(Move::try_from)
+                .collect::<Result<Vec<_>, _>>()?;
+            let moves = moves.into_iter().map(|m| (m.dir, m.length));
+
+            Ok::<_, String>(Wire::from_moves(Point::default(), moves))
+        })?;
+
+        if wires.len() < 2 {
+            return Err(format!("Expected at least 2 wires, got {}", wires.len()).into());
         }
 
-        total += line.length();
+        Ok(wires)
     }
 
-    unreachable!();
-}
-
-pub fn solve() {
-    let wires_moves: Vec<Vec<Move>> = INPUT
-        .lines()
-        .map(|l| l.split(',').map(|m| Move::from(m)).collect())
-        .collect();
-
-    let wire1_path = create_lines_from_moves(&wires_moves[0]);
-    let wire2_path = create_lines_from_moves(&wires_moves[1]);
-
-    let intersections = find_path_intersections(&wire1_path, &wire2_path);
-    let min = intersections.iter().map(|p| p.manhattan_distance()).min();
-    println!("3.1 {:?}", min);
-
-    let mut steps = Vec::new();
-
-    for intersection in intersections {
-        let wire1_steps = count_steps_to_intersection(&wire1_path, &intersection);
-        let wire2_steps = count_steps_to_intersection(&wire2_path, &intersection);
-        steps.push(wire1_steps + wire2_steps);
+    fn part1(wires: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        crossings(wires)
+            .map(|(p, _)| p.manhattan())
+            .min()
+            .ok_or_else(|| "The wires don't intersect".into())
     }
 
-    let min = steps.iter().min();
-    println!("3.2 {:?}", min);
+    fn part2(wires: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        crossings(wires)
+            .map(|(_, steps)| steps)
+            .min()
+            .ok_or_else(|| "The wires don't intersect".into())
+    }
 }
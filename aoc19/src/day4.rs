@@ -1,3 +1,10 @@
+use aoc_common::{parse, Solution};
+use std::error::Error;
+
+pub static INPUT: &str = "172851-675869";
+
+pub struct Day4;
+
 struct Passwords {
     higher: u32,
     current: String,
@@ -42,7 +49,7 @@ impl Passwords {
             UniquePrevious,
             TwoAdjacent,
             ThreeAdjacent,
-        };
+        }
 
         let mut prev = self.current.chars().next().unwrap();
         let mut state = State::UniquePrevious;
@@ -84,13 +91,30 @@ impl Passwords {
     }
 }
 
-pub fn solve() {
-    let lower = 172_851;
-    let higher = 675_869;
+impl Solution for Day4 {
+    type Input = (u32, u32);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        let ranges = parse::lines(input, |line| {
+            let (lower, higher) = line
+                .split_once('-')
+                .ok_or("Expected a range like 100-200")?;
+            Ok::<_, String>((parse::number(lower)?, parse::number(higher)?))
+        })?;
 
-    let passwords = Passwords::new(lower, higher, false);
-    println!("4.1 {}", passwords.count());
+        match ranges[..] {
+            [range] => Ok(range),
+            _ => Err(format!("Expected one range, got {}", ranges.len()).into()),
+        }
+    }
 
-    let passwords = Passwords::new(lower, higher, true);
-    println!("4.2 {}", passwords.count());
+    fn part1(&(lower, higher): &Self::Input) -> Result<usize, Box<dyn Error>> {
+        Ok(Passwords::new(lower, higher, false).count())
+    }
+
+    fn part2(&(lower, higher): &Self::Input) -> Result<usize, Box<dyn Error>> {
+        Ok(Passwords::new(lower, higher, true).count())
+    }
 }
@@ -1,29 +1,46 @@
-use crate::intcode::{Computer, StepResult};
+use crate::intcode::{parse_program, Computer, StepResult};
+use aoc_common::Solution;
 use std::error::Error;
 
-static INPUT: &str = include_str!("../res/5");
+pub static INPUT: &str = include_str!("../res/5");
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-    let program: Vec<i64> = INPUT
-        .split(',')
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
+pub struct Day5;
 
-    let mut last_output: i64 = 0;
+/// Runs the diagnostic program for a system, returning the diagnostic code it
+/// outputs last.
+fn run_diagnostics(program: &[i64], system_id: i64) -> Result<i64, Box<dyn Error>> {
+    let mut cpu = Computer::new(program);
+    cpu.add_input(system_id);
 
-    match Computer::new(&program).add_input(1).run()? {
-        StepResult::OutputAvailable(output) => last_output = output,
-        StepResult::Finished => println!("5.2 {:?}", last_output),
-        StepResult::NeedInput => panic!("Program needs input"),
+    let mut last_output = None;
+
+    loop {
+        match cpu.run()? {
+            StepResult::OutputAvailable(output) => last_output = Some(output),
+            StepResult::Finished => break,
+            StepResult::NeedInput => return Err("Program needs input".into()),
+        }
     }
 
-    match Computer::new(&program).add_input(5).run()? {
-        StepResult::OutputAvailable(output) => println!("5.2 {:?}", output),
-        StepResult::Finished => panic!("Program finished before returning output"),
-        StepResult::NeedInput => panic!("Program needs input"),
+    last_output.ok_or_else(|| "Program finished before returning output".into())
+}
+
+impl Solution for Day5 {
+    type Input = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        parse_program(input)
     }
 
-    Ok(())
+    fn part1(program: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        run_diagnostics(program, 1)
+    }
+
+    fn part2(program: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        run_diagnostics(program, 5)
+    }
 }
 
 #[cfg(test)]
@@ -1,64 +1,90 @@
+use aoc_common::{parse, Example, Solution};
 use std::collections::HashMap;
+use std::error::Error;
 
-static INPUT: &str = include_str!("../res/6");
+pub static INPUT: &str = include_str!("../res/6");
 
-pub fn solve() {
-    let mut orbits = HashMap::new();
+pub struct Day6;
 
-    for line in INPUT.lines() {
-        let mut names = line.split(")");
-        let parent = names.next().unwrap();
-        let child = names.next().unwrap();
+fn ancestors_between(child: &str, ancestor: &str, orbits: &HashMap<String, String>) -> Option<u32> {
+    let mut count = 0;
+    let mut cur = child;
 
-        orbits.insert(child, parent);
+    while cur != ancestor {
+        cur = orbits.get(cur)?;
+        count += 1;
     }
 
-    let mut total = 0;
+    Some(count)
+}
 
-    for (object, parent) in orbits.iter() {
-        if *object == "COM" {
-            continue;
-        }
+impl Solution for Day6 {
+    /// Each object mapped to the object it orbits.
+    type Input = HashMap<String, String>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1(
+            "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L",
+            "42",
+        ),
+        Example::part2(
+            "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN",
+            "4",
+        ),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        let orbits = parse::lines(input, |line| {
+            let (parent, child) = line
+                .trim()
+                .split_once(')')
+                .ok_or("Expected an orbit like A)B")?;
+            Ok::<_, &str>((child.to_string(), parent.to_string()))
+        })?;
+
+        Ok(orbits.into_iter().collect())
+    }
 
-        total += 1;
+    fn part1(orbits: &Self::Input) -> Result<u32, Box<dyn Error>> {
+        let mut total = 0;
 
-        let mut cur = parent;
+        for (object, parent) in orbits.iter() {
+            if *object == "COM" {
+                continue;
+            }
 
-        while *cur != "COM" {
-            cur = orbits.get(cur).unwrap();
             total += 1;
-        }
-    }
-
-    println!("6.1 {}", total);
 
-    fn ancestors_between(child: &str, ancestor: &str, orbits: &HashMap<&str, &str>) -> Option<u32> {
-        let mut count = 0;
-        let mut cur = child;
+            let mut cur = parent;
 
-        while cur != ancestor {
-            cur = orbits.get(cur)?;
-            count += 1;
+            while *cur != "COM" {
+                cur = orbits
+                    .get(cur)
+                    .ok_or_else(|| format!("{} doesn't orbit anything", cur))?;
+                total += 1;
+            }
         }
 
-        Some(count)
+        Ok(total)
     }
 
-    let mut cur = orbits["YOU"];
-    let mut my_hops = 0;
+    fn part2(orbits: &Self::Input) -> Result<u32, Box<dyn Error>> {
+        let mut cur = orbits.get("YOU").ok_or("YOU doesn't orbit anything")?;
+        let mut my_hops = 0;
 
-    let total = loop {
-        if let Some(v) = ancestors_between("SAN", cur, &orbits) {
-            break Some(my_hops + v - 1);
-        }
-
-        if cur == "COM" {
-            break None;
-        }
+        loop {
+            if let Some(v) = ancestors_between("SAN", cur, orbits) {
+                return Ok(my_hops + v - 1);
+            }
 
-        cur = orbits[cur];
-        my_hops += 1;
-    };
+            if cur == "COM" {
+                return Err("YOU and SAN don't share an ancestor".into());
+            }
 
-    println!("6.2 {:?}", total);
+            cur = &orbits[cur];
+            my_hops += 1;
+        }
+    }
 }
@@ -1,16 +1,39 @@
-use crate::intcode::{Computer, StepResult};
+use crate::intcode::{parse_program, Computer, StepResult};
+use aoc_common::{Example, Solution};
 use std::error::Error;
 
-static INPUT: &str = include_str!("../res/7");
+pub static INPUT: &str = include_str!("../res/7");
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-    let result = solve_with_input(INPUT, false)?;
-    println!("7.1 {:?}", result);
+pub struct Day7;
 
-    let result = solve_with_input(INPUT, true)?;
-    println!("7.2 {:?}", result);
+impl Solution for Day7 {
+    type Input = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    Ok(())
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0", "43210"),
+        Example::part1(
+            "3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0",
+            "54321",
+        ),
+        Example::part2(
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
+            "139629729",
+        ),
+    ];
+
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        parse_program(input)
+    }
+
+    fn part1(program: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        maximum_signal(program, false)
+    }
+
+    fn part2(program: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        maximum_signal(program, true)
+    }
 }
 
 fn run_circuit(amps: &mut [&mut Computer]) -> i64 {
@@ -34,22 +57,17 @@ fn run_circuit(amps: &mut [&mut Computer]) -> i64 {
     }
 }
 
-fn solve_with_input(input: &str, feedback_mode: bool) -> Result<i64, Box<dyn Error>> {
-    let program: Vec<i64> = input
-        .split(',')
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
-
+fn maximum_signal(program: &[i64], feedback_mode: bool) -> Result<i64, Box<dyn Error>> {
     let mut maximum_output: Option<i64> = None;
 
     let (lower, upper) = if feedback_mode { (5, 10) } else { (0, 5) };
 
     // TODO: Support any number of amps
-    let mut amp_a = Computer::new(&program);
-    let mut amp_b = Computer::new(&program);
-    let mut amp_c = Computer::new(&program);
-    let mut amp_d = Computer::new(&program);
-    let mut amp_e = Computer::new(&program);
+    let mut amp_a = Computer::new(program);
+    let mut amp_b = Computer::new(program);
+    let mut amp_c = Computer::new(program);
+    let mut amp_d = Computer::new(program);
+    let mut amp_e = Computer::new(program);
 
     for setting_a in lower..upper {
         for setting_b in lower..upper {
@@ -100,29 +118,5 @@ fn solve_with_input(input: &str, feedback_mode: bool) -> Result<i64, Box<dyn Err
         }
     }
 
-    Ok(maximum_output.expect("No maximum output"))
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn regular() {
-        let result =
-            solve_with_input("3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0", false).unwrap();
-        let expected = 43_210;
-        assert_eq!(result, expected);
-    }
-
-    #[test]
-    fn feedback() {
-        let result = solve_with_input(
-            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5",
-            true,
-        )
-        .unwrap();
-        let expected = 139_629_729;
-        assert_eq!(result, expected);
-    }
+    maximum_output.ok_or_else(|| "No maximum output".into())
 }
@@ -1,52 +1,54 @@
-use crate::intcode::{Computer, StepResult};
+use crate::intcode::{parse_program, Computer, StepResult};
+use aoc_common::{Example, Solution};
 use std::error::Error;
 
-static INPUT: &str = include_str!("../res/9");
+pub static INPUT: &str = include_str!("../res/9");
 
-pub fn solve() -> Result<(), Box<dyn Error>> {
-    part1()?;
-    part2()?;
-    Ok(())
-}
+pub struct Day9;
 
-pub fn part1() -> Result<(), Box<dyn Error>> {
-    let program: Vec<i64> = INPUT
-        .split(',')
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
+/// Runs BOOST in the given mode. It outputs any opcodes which are
+/// malfunctioning before its final output.
+fn run_boost(program: &[i64], mode: i64) -> Result<i64, Box<dyn Error>> {
+    let mut cpu = Computer::new(program);
+    cpu.add_input(mode);
 
-    let mut cpu = Computer::new(&program);
-    cpu.add_input(1);
+    let mut outputs = Vec::new();
 
     loop {
         match cpu.run()? {
-            StepResult::OutputAvailable(v) => println!("9.1 {}", v),
+            StepResult::OutputAvailable(v) => outputs.push(v),
             StepResult::Finished => break,
-            output => panic!("Unexpected output: {:?}", output),
+            output => return Err(format!("Unexpected output: {:?}", output).into()),
         }
     }
 
-    Ok(())
+    match outputs.as_slice() {
+        [output] => Ok(*output),
+        _ => Err(format!("Malfunctioning opcodes: {:?}", outputs).into()),
+    }
 }
 
-pub fn part2() -> Result<(), Box<dyn Error>> {
-    let program: Vec<i64> = INPUT
-        .split(',')
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
+impl Solution for Day9 {
+    type Input = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    let mut cpu = Computer::new(&program);
-    cpu.add_input(2);
+    const EXAMPLES: &'static [Example] = &[
+        Example::part1("1102,34915192,34915192,7,4,7,99,0", "1219070632396864"),
+        Example::part1("104,1125899906842624,99", "1125899906842624"),
+    ];
 
-    loop {
-        match cpu.run()? {
-            StepResult::OutputAvailable(v) => println!("9.2 {}", v),
-            StepResult::Finished => break,
-            output => panic!("Unexpected output: {:?}", output),
-        }
+    fn parse(input: &str) -> Result<Self::Input, Box<dyn Error>> {
+        parse_program(input)
+    }
+
+    fn part1(program: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        run_boost(program, 1)
     }
 
-    Ok(())
+    fn part2(program: &Self::Input) -> Result<i64, Box<dyn Error>> {
+        run_boost(program, 2)
+    }
 }
 
 #[cfg(test)]
@@ -63,12 +65,8 @@ mod tests {
         let mut cpu = Computer::new(&program);
         let mut output = Vec::new();
 
-        loop {
-            if let StepResult::OutputAvailable(o) = cpu.run()? {
-                output.push(o);
-            } else {
-                break;
-            }
+        while let StepResult::OutputAvailable(o) = cpu.run()? {
+            output.push(o);
         }
 
         assert_eq!(
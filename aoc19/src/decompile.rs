@@ -0,0 +1,1184 @@
+//! Lifts intcode programs in to structured pseudo-code.
+//!
+//! Code is discovered by following control flow from address 0, so data is
+//! never decoded as instructions. Functions are inferred from the calling
+//! convention used by the day 9+ programs: the caller stores its arguments in
+//! `[rb+1..]` and the return address in `[rb+0]` before jumping, the callee's
+//! `ModifyBase` prologue moves the base past them, and its epilogue moves it
+//! back before jumping to `[rb+0]`.
+//!
+//! Self-modifying code isn't modelled, the output describes the program as it
+//! was loaded.
+
+use crate::intcode::{Mode, Opcode};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+
+/// A stand in node for "the function has returned" when computing post
+/// dominators.
+const EXIT: usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Value {
+    Const(i64),
+    Mem(i64),
+    Rel(i64),
+    Deref(i64),
+}
+
+impl Value {
+    /// Jump targets and other inputs are loaded with the operand's mode.
+    fn load(mode: Mode, param: i64) -> Self {
+        match mode {
+            Mode::Indirect => Value::Mem(param),
+            Mode::Immediate => Value::Const(param),
+            Mode::Relative => Value::Rel(param),
+        }
+    }
+
+    /// Destinations are addresses, so an immediate destination is a memory
+    /// cell rather than a constant.
+    fn store(mode: Mode, param: i64) -> Self {
+        match mode {
+            Mode::Indirect => Value::Deref(param),
+            Mode::Immediate => Value::Mem(param),
+            Mode::Relative => Value::Rel(param),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Cond {
+    value: Value,
+    is_zero: bool,
+}
+
+impl Cond {
+    fn negate(self) -> Self {
+        Cond {
+            value: self.value,
+            is_zero: !self.is_zero,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Value(Value),
+    Binary(Value, &'static str, Value),
+}
+
+impl Expr {
+    fn reads(&self) -> Vec<Value> {
+        match self {
+            Expr::Value(v) => vec![*v],
+            Expr::Binary(l, _, r) => vec![*l, *r],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Stmt {
+    Assign(Value, Expr),
+    Input(Value),
+    Output(Value),
+    AdjustBase(Value),
+    Call(usize, Vec<Expr>),
+    JumpIf(Cond, Value),
+}
+
+impl Stmt {
+    fn is_nop(&self) -> bool {
+        match self {
+            Stmt::Assign(dest, Expr::Value(value)) => dest == value,
+            Stmt::AdjustBase(Value::Const(0)) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Exit {
+    Goto(usize),
+    Branch(Cond, usize, usize),
+    Jump(Value),
+    Halt,
+    Invalid(i64),
+}
+
+/// How an instruction affects control flow.
+enum Flow {
+    Next,
+    Jump(usize),
+    Branch(Cond, usize),
+    Indirect(Option<Cond>, Value),
+    Halt,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Instruction {
+    opcode: Opcode,
+    params: [i64; 3],
+}
+
+impl Instruction {
+    fn decode(program: &[i64], addr: usize) -> Result<Self, i64> {
+        let load = |addr: usize| program.get(addr).copied().unwrap_or(0);
+        let value = load(addr);
+        let opcode = Opcode::try_from(value).map_err(|_| value)?;
+
+        Ok(Instruction {
+            opcode,
+            params: [load(addr + 1), load(addr + 2), load(addr + 3)],
+        })
+    }
+
+    /// `Opcode::len` is 0 for `End` because the computer never steps past it.
+    fn len(&self) -> usize {
+        self.opcode.len().max(1)
+    }
+
+    fn flow(&self) -> Flow {
+        let (cond_mode, target_mode, jump_when_zero) = match self.opcode {
+            Opcode::JumpIfTrue(c, t) => (c, t, false),
+            Opcode::JumpIfFalse(c, t) => (c, t, true),
+            Opcode::End => return Flow::Halt,
+            _ => return Flow::Next,
+        };
+
+        let target = Value::load(target_mode, self.params[1]);
+
+        if cond_mode == Mode::Immediate {
+            if (self.params[0] == 0) != jump_when_zero {
+                return Flow::Next;
+            }
+
+            return match target {
+                Value::Const(addr) if addr >= 0 => Flow::Jump(addr as usize),
+                _ => Flow::Indirect(None, target),
+            };
+        }
+
+        let cond = Cond {
+            value: Value::load(cond_mode, self.params[0]),
+            is_zero: jump_when_zero,
+        };
+
+        match target {
+            Value::Const(addr) if addr >= 0 => Flow::Branch(cond, addr as usize),
+            _ => Flow::Indirect(Some(cond), target),
+        }
+    }
+
+    /// The value stored by an instruction like `21101 13 0 0`, which is how
+    /// callers save their return address.
+    fn relative_constant(&self) -> Option<i64> {
+        match self.opcode {
+            Opcode::Add(Mode::Immediate, Mode::Immediate, Mode::Relative) => {
+                Some(self.params[0] + self.params[1])
+            }
+            Opcode::Multiply(Mode::Immediate, Mode::Immediate, Mode::Relative) => {
+                Some(self.params[0] * self.params[1])
+            }
+            _ => None,
+        }
+    }
+
+    /// Only called for instructions whose flow is `Flow::Next`.
+    fn lower(&self) -> Option<Stmt> {
+        let p = self.params;
+
+        let binary = |l: Mode, r: Mode, d: Mode, op: &'static str| {
+            let left = Value::load(l, p[0]);
+            let right = Value::load(r, p[1]);
+            let expr = match (left, op, right) {
+                (Value::Const(l), "+", Value::Const(r)) => Expr::Value(Value::Const(l + r)),
+                (Value::Const(l), "*", Value::Const(r)) => Expr::Value(Value::Const(l * r)),
+                (v, "+", Value::Const(0)) | (Value::Const(0), "+", v) => Expr::Value(v),
+                (v, "*", Value::Const(1)) | (Value::Const(1), "*", v) => Expr::Value(v),
+                _ => Expr::Binary(left, op, right),
+            };
+            Stmt::Assign(Value::store(d, p[2]), expr)
+        };
+
+        let stmt = match self.opcode {
+            Opcode::Add(l, r, d) => binary(l, r, d, "+"),
+            Opcode::Multiply(l, r, d) => binary(l, r, d, "*"),
+            Opcode::IsLess(l, r, d) => binary(l, r, d, "<"),
+            Opcode::IsEqual(l, r, d) => binary(l, r, d, "=="),
+            Opcode::Input(d) => Stmt::Input(Value::store(d, p[0])),
+            Opcode::Output(m) => Stmt::Output(Value::load(m, p[0])),
+            Opcode::ModifyBase(m) => Stmt::AdjustBase(Value::load(m, p[0])),
+            // Jumps which are never taken.
+            Opcode::JumpIfTrue(_, _) | Opcode::JumpIfFalse(_, _) | Opcode::End => return None,
+        };
+
+        Some(stmt)
+    }
+}
+
+#[derive(Debug)]
+struct Block {
+    stmts: Vec<Stmt>,
+    exit: Exit,
+}
+
+impl Block {
+    fn successors(&self) -> Vec<usize> {
+        match self.exit {
+            Exit::Goto(t) => vec![t],
+            Exit::Branch(_, t, f) => vec![t, f],
+            _ => Vec::new(),
+        }
+    }
+}
+
+struct Function {
+    entry: usize,
+    /// Where the body starts, after any prologue.
+    body: usize,
+    frame: i64,
+    args: usize,
+}
+
+/// Everything reachable from address 0.
+#[derive(Default)]
+struct Listing {
+    instructions: BTreeMap<usize, Instruction>,
+    invalid: BTreeMap<usize, i64>,
+    leaders: BTreeSet<usize>,
+    calls: HashMap<usize, usize>,
+    return_stores: HashSet<usize>,
+    entries: BTreeSet<usize>,
+}
+
+impl Listing {
+    fn discover(program: &[i64]) -> Self {
+        let mut listing = Listing::default();
+        let mut work = vec![0];
+        listing.leaders.insert(0);
+
+        while let Some(mut addr) = work.pop() {
+            // (address, value) of recent constant stores relative to the base.
+            let mut constants: Vec<(usize, i64)> = Vec::new();
+
+            loop {
+                if listing.instructions.contains_key(&addr) || listing.invalid.contains_key(&addr) {
+                    listing.leaders.insert(addr);
+                    break;
+                }
+
+                let inst = match Instruction::decode(program, addr) {
+                    Ok(inst) => inst,
+                    Err(value) => {
+                        listing.invalid.insert(addr, value);
+                        break;
+                    }
+                };
+
+                listing.instructions.insert(addr, inst);
+                let next = addr + inst.len();
+
+                match inst.flow() {
+                    Flow::Next => {
+                        if let Some(value) = inst.relative_constant() {
+                            constants.push((addr, value));
+                        }
+                    }
+                    Flow::Jump(target) => {
+                        let store = constants.iter().rev().find(|(_, v)| *v == next as i64);
+                        listing.leaders.insert(target);
+                        work.push(target);
+
+                        match store {
+                            Some(&(store, _)) => {
+                                listing.calls.insert(addr, target);
+                                listing.return_stores.insert(store);
+                                listing.entries.insert(target);
+                                constants.clear();
+                            }
+                            None => break,
+                        }
+                    }
+                    Flow::Branch(_, target) => {
+                        listing.leaders.insert(target);
+                        listing.leaders.insert(next);
+                        work.push(target);
+                        constants.clear();
+                    }
+                    Flow::Indirect(Some(_), _) => {
+                        listing.leaders.insert(next);
+                        constants.clear();
+                    }
+                    Flow::Indirect(None, _) | Flow::Halt => break,
+                }
+
+                addr = next;
+            }
+        }
+
+        listing
+    }
+
+    fn prologue(&self, entry: usize) -> Option<i64> {
+        match self.instructions.get(&entry)? {
+            Instruction {
+                opcode: Opcode::ModifyBase(Mode::Immediate),
+                params: [frame, _, _],
+            } if *frame > 0 => Some(*frame),
+            _ => None,
+        }
+    }
+
+    fn functions(&mut self) -> Vec<Function> {
+        let mut functions = vec![Function {
+            entry: 0,
+            body: 0,
+            frame: 0,
+            args: 0,
+        }];
+
+        for &entry in &self.entries {
+            if entry == 0 {
+                continue;
+            }
+
+            let (body, frame) = match self.prologue(entry) {
+                Some(frame) => (entry + 2, frame),
+                None => (entry, 0),
+            };
+
+            functions.push(Function {
+                entry,
+                body,
+                frame,
+                args: 0,
+            });
+        }
+
+        for f in &functions {
+            self.leaders.insert(f.body);
+        }
+
+        functions
+    }
+
+    fn blocks(&self) -> BTreeMap<usize, Block> {
+        let mut blocks = BTreeMap::new();
+
+        for &leader in &self.leaders {
+            let mut stmts = Vec::new();
+            let mut addr = leader;
+
+            let exit = loop {
+                let inst = match self.instructions.get(&addr) {
+                    Some(inst) => inst,
+                    None => break Exit::Invalid(self.invalid.get(&addr).copied().unwrap_or(0)),
+                };
+
+                let next = addr + inst.len();
+
+                if let Some(&target) = self.calls.get(&addr) {
+                    let args = take_arguments(&mut stmts);
+                    stmts.push(Stmt::Call(target, args));
+                } else {
+                    match inst.flow() {
+                        Flow::Next => {
+                            if !self.return_stores.contains(&addr) {
+                                stmts.extend(inst.lower().filter(|s| !s.is_nop()));
+                            }
+                        }
+                        Flow::Jump(target) => break Exit::Goto(target),
+                        Flow::Branch(cond, target) => break Exit::Branch(cond, target, next),
+                        Flow::Indirect(Some(cond), target) => {
+                            stmts.push(Stmt::JumpIf(cond, target))
+                        }
+                        Flow::Indirect(None, target) => break Exit::Jump(target),
+                        Flow::Halt => break Exit::Halt,
+                    }
+                }
+
+                if self.leaders.contains(&next) {
+                    break Exit::Goto(next);
+                }
+
+                addr = next;
+            };
+
+            blocks.insert(leader, Block { stmts, exit });
+        }
+
+        blocks
+    }
+}
+
+/// Moves the trailing stores to `[rb+1..]` before a call in to its argument
+/// list, as long as none of them read a slot written by an earlier one.
+fn take_arguments(stmts: &mut Vec<Stmt>) -> Vec<Expr> {
+    let start = stmts
+        .iter()
+        .rposition(|s| !matches!(s, Stmt::Assign(Value::Rel(offset), _) if *offset > 0))
+        .map_or(0, |i| i + 1);
+
+    let mut written = Vec::new();
+
+    for stmt in &stmts[start..] {
+        if let Stmt::Assign(dest, expr) = stmt {
+            if written.contains(dest) || expr.reads().iter().any(|v| written.contains(v)) {
+                return Vec::new();
+            }
+            written.push(*dest);
+        }
+    }
+
+    let mut args: Vec<(i64, Expr)> = stmts
+        .drain(start..)
+        .filter_map(|s| match s {
+            Stmt::Assign(Value::Rel(offset), expr) => Some((offset, expr)),
+            _ => None,
+        })
+        .collect();
+
+    args.sort_by_key(|(offset, _)| *offset);
+    args.into_iter().map(|(_, expr)| expr).collect()
+}
+
+fn reverse_postorder(entry: usize, succs: &HashMap<usize, Vec<usize>>) -> Vec<usize> {
+    let mut order = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![(entry, 0)];
+    seen.insert(entry);
+
+    while let Some((node, i)) = stack.pop() {
+        let children = succs.get(&node).map_or(&[][..], |s| &s[..]);
+
+        if let Some(&child) = children.get(i) {
+            stack.push((node, i + 1));
+            if seen.insert(child) {
+                stack.push((child, 0));
+            }
+        } else {
+            order.push(node);
+        }
+    }
+
+    order.reverse();
+    order
+}
+
+/// Cooper, Harvey and Kennedy's "A Simple, Fast Dominance Algorithm".
+fn immediate_dominators(entry: usize, succs: &HashMap<usize, Vec<usize>>) -> HashMap<usize, usize> {
+    let order = reverse_postorder(entry, succs);
+    let index: HashMap<usize, usize> = order.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+
+    let mut preds: HashMap<usize, Vec<usize>> = HashMap::new();
+    for &node in &order {
+        for &succ in succs.get(&node).into_iter().flatten() {
+            preds.entry(succ).or_default().push(node);
+        }
+    }
+
+    let mut idom = HashMap::new();
+    idom.insert(entry, entry);
+
+    let intersect = |idom: &HashMap<usize, usize>, mut a: usize, mut b: usize| {
+        while a != b {
+            while index[&a] > index[&b] {
+                a = idom[&a];
+            }
+            while index[&b] > index[&a] {
+                b = idom[&b];
+            }
+        }
+        a
+    };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for &node in order.iter().skip(1) {
+            let mut new_idom = None;
+
+            for &pred in preds.get(&node).into_iter().flatten() {
+                if idom.contains_key(&pred) {
+                    new_idom = Some(match new_idom {
+                        None => pred,
+                        Some(other) => intersect(&idom, pred, other),
+                    });
+                }
+            }
+
+            if let Some(new_idom) = new_idom {
+                if idom.insert(node, new_idom) != Some(new_idom) {
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    idom
+}
+
+struct Loop {
+    body: HashSet<usize>,
+    follow: Option<usize>,
+}
+
+/// The control flow graph of a single function.
+struct Graph {
+    succs: HashMap<usize, Vec<usize>>,
+    preds: HashMap<usize, usize>,
+    ipdom: HashMap<usize, usize>,
+    loops: HashMap<usize, Loop>,
+    nodes: Vec<usize>,
+}
+
+impl Graph {
+    fn new(entry: usize, blocks: &BTreeMap<usize, Block>) -> Self {
+        let mut succs = HashMap::new();
+        let mut work = vec![entry];
+
+        while let Some(node) = work.pop() {
+            if succs.contains_key(&node) {
+                continue;
+            }
+
+            let children = blocks.get(&node).map(Block::successors).unwrap_or_default();
+            work.extend(&children);
+            succs.insert(node, children);
+        }
+
+        let mut nodes: Vec<usize> = succs.keys().copied().collect();
+        nodes.sort_unstable();
+
+        let idom = immediate_dominators(entry, &succs);
+        let dominates = |a: usize, mut b: usize| loop {
+            if a == b {
+                return true;
+            }
+            match idom.get(&b) {
+                Some(&d) if d != b => b = d,
+                _ => return false,
+            }
+        };
+
+        let mut reversed: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (&node, children) in &succs {
+            if children.is_empty() {
+                reversed.entry(EXIT).or_default().push(node);
+            }
+            for &child in children {
+                reversed.entry(child).or_default().push(node);
+            }
+        }
+
+        let ipdom = immediate_dominators(EXIT, &reversed)
+            .into_iter()
+            .filter(|&(node, d)| node != EXIT && d != EXIT)
+            .collect();
+
+        let mut loops: HashMap<usize, Loop> = HashMap::new();
+
+        for &node in &nodes {
+            for &header in &succs[&node] {
+                if !dominates(header, node) {
+                    continue;
+                }
+
+                let body = &mut loops
+                    .entry(header)
+                    .or_insert_with(|| Loop {
+                        body: vec![header].into_iter().collect(),
+                        follow: None,
+                    })
+                    .body;
+
+                let mut work = vec![node];
+                while let Some(n) = work.pop() {
+                    if body.insert(n) {
+                        work.extend(
+                            reversed
+                                .get(&n)
+                                .into_iter()
+                                .flatten()
+                                .filter(|&&p| p != EXIT),
+                        );
+                    }
+                }
+            }
+        }
+
+        let preds = nodes
+            .iter()
+            .map(|n| (*n, reversed.get(n).map_or(0, Vec::len)))
+            .collect();
+
+        let mut graph = Graph {
+            succs,
+            preds,
+            ipdom,
+            loops,
+            nodes,
+        };
+
+        let follows: Vec<(usize, Option<usize>)> = graph
+            .loops
+            .iter()
+            .map(|(&header, lp)| {
+                let exits: BTreeSet<usize> = lp
+                    .body
+                    .iter()
+                    .flat_map(|n| graph.succs[n].iter().copied())
+                    .filter(|n| !lp.body.contains(n))
+                    .collect();
+
+                let follow = match graph.ipdom.get(&header) {
+                    Some(d) if exits.contains(d) => Some(*d),
+                    _ => exits.iter().next().copied(),
+                };
+
+                (header, follow)
+            })
+            .collect();
+
+        for (header, follow) in follows {
+            graph.loops.get_mut(&header).unwrap().follow = follow;
+        }
+
+        graph
+    }
+}
+
+struct Line {
+    depth: usize,
+    text: String,
+    label: Option<usize>,
+}
+
+struct Emitter<'a> {
+    blocks: &'a BTreeMap<usize, Block>,
+    functions: &'a BTreeMap<usize, Function>,
+    function: &'a Function,
+    graph: Graph,
+    emitted: HashSet<usize>,
+    targets: HashSet<usize>,
+    lines: Vec<Line>,
+}
+
+impl<'a> Emitter<'a> {
+    fn value(&self, value: Value) -> String {
+        let frame = self.function.frame;
+
+        match value {
+            Value::Const(v) => v.to_string(),
+            Value::Mem(addr) => format!("[{}]", addr),
+            Value::Deref(addr) => format!("[[{}]]", addr),
+            Value::Rel(offset) if frame > 0 && offset > -frame && offset < 0 => {
+                let slot = (offset + frame) as usize;
+                if slot <= self.function.args {
+                    format!("arg{}", slot)
+                } else {
+                    format!("local{}", slot)
+                }
+            }
+            Value::Rel(0) => "[rb]".to_string(),
+            Value::Rel(offset) if offset < 0 => format!("[rb-{}]", -offset),
+            Value::Rel(offset) => format!("[rb+{}]", offset),
+        }
+    }
+
+    fn expr(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Value(v) => self.value(*v),
+            Expr::Binary(l, "+", Value::Const(r)) if *r < 0 => {
+                format!("{} - {}", self.value(*l), -r)
+            }
+            Expr::Binary(l, op, r) => format!("{} {} {}", self.value(*l), op, self.value(*r)),
+        }
+    }
+
+    fn cond(&self, cond: Cond) -> String {
+        let op = if cond.is_zero { "==" } else { "!=" };
+        format!("{} {} 0", self.value(cond.value), op)
+    }
+
+    fn stmt(&self, stmt: &Stmt) -> String {
+        match stmt {
+            Stmt::Assign(dest, expr) => format!("{} = {}", self.value(*dest), self.expr(expr)),
+            Stmt::Input(dest) => format!("{} = input()", self.value(*dest)),
+            Stmt::Output(value) => format!("output({})", self.value(*value)),
+            Stmt::AdjustBase(Value::Const(v)) if *v < 0 => format!("rb -= {}", -v),
+            Stmt::AdjustBase(value) => format!("rb += {}", self.value(*value)),
+            Stmt::Call(target, args) => {
+                let args: Vec<String> = args.iter().map(|a| self.expr(a)).collect();
+                format!("{}({})", name(*target, self.functions), args.join(", "))
+            }
+            Stmt::JumpIf(cond, target) => {
+                format!(
+                    "if {} {{ goto *{} }}",
+                    self.cond(*cond),
+                    self.value(*target)
+                )
+            }
+        }
+    }
+
+    fn line(&mut self, depth: usize, text: String) {
+        self.lines.push(Line {
+            depth,
+            text,
+            label: None,
+        });
+    }
+
+    /// What `region` would emit for `node` without emitting any code.
+    fn jump_to(&self, node: usize, loops: &[usize]) -> Option<String> {
+        if let Some(header) = loops.last() {
+            if node == *header {
+                return Some("continue".to_string());
+            }
+            if Some(node) == self.graph.loops[header].follow {
+                return Some("break".to_string());
+            }
+        }
+
+        if self.emitted.contains(&node) {
+            Some(format!("goto L{}", node))
+        } else {
+            None
+        }
+    }
+
+    fn region(
+        &mut self,
+        mut node: usize,
+        stop: Option<usize>,
+        loops: &mut Vec<usize>,
+        depth: usize,
+    ) {
+        loop {
+            if Some(node) == stop {
+                return;
+            }
+
+            if let Some(jump) = self.jump_to(node, loops) {
+                if jump.starts_with("goto") {
+                    self.targets.insert(node);
+                }
+                self.line(depth, jump);
+                return;
+            }
+
+            let next = if self.graph.loops.contains_key(&node) {
+                self.loop_(node, loops, depth)
+            } else {
+                self.block(node, loops, depth)
+            };
+
+            match next {
+                Some(next) => node = next,
+                None => return,
+            }
+        }
+    }
+
+    fn loop_(&mut self, header: usize, loops: &mut Vec<usize>, depth: usize) -> Option<usize> {
+        let follow = self.graph.loops[&header].follow;
+        let block = &self.blocks[&header];
+        loops.push(header);
+
+        let while_cond = match block.exit {
+            Exit::Branch(cond, t, f) if block.stmts.is_empty() => {
+                if Some(f) == follow {
+                    Some((cond, t))
+                } else if Some(t) == follow {
+                    Some((cond.negate(), f))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        if let Some((cond, body)) = while_cond {
+            self.emitted.insert(header);
+            self.lines.push(Line {
+                depth,
+                text: format!("while {} {{", self.cond(cond)),
+                label: Some(header),
+            });
+            self.region(body, None, loops, depth + 1);
+        } else {
+            self.line(depth, "loop {".to_string());
+            if let Some(next) = self.block(header, loops, depth + 1) {
+                self.region(next, None, loops, depth + 1);
+            }
+        }
+
+        if let Some(last) = self.lines.last() {
+            if last.depth == depth + 1 && last.text == "continue" {
+                self.lines.pop();
+            }
+        }
+
+        loops.pop();
+        self.line(depth, "}".to_string());
+        follow
+    }
+
+    fn block(&mut self, node: usize, loops: &mut Vec<usize>, depth: usize) -> Option<usize> {
+        self.emitted.insert(node);
+        let block = &self.blocks[&node];
+
+        self.lines.push(Line {
+            depth,
+            text: String::new(),
+            label: Some(node),
+        });
+
+        let function = self.function;
+        let mut stmts = &block.stmts[..];
+
+        // The epilogue is implied by the return.
+        if let (Some(Stmt::AdjustBase(Value::Const(v))), Exit::Jump(Value::Rel(_))) =
+            (stmts.last(), &block.exit)
+        {
+            if function.frame > 0 && *v == -function.frame {
+                stmts = &stmts[..stmts.len() - 1];
+            }
+        }
+
+        for stmt in stmts {
+            let text = self.stmt(stmt);
+            self.line(depth, text);
+        }
+
+        match block.exit {
+            Exit::Goto(target) => Some(target),
+            Exit::Branch(cond, t, f) => self.branch(node, cond, t, f, loops, depth),
+            Exit::Jump(Value::Rel(_)) if function.frame > 0 => {
+                self.line(depth, "return".to_string());
+                None
+            }
+            Exit::Jump(target) => {
+                let text = format!("goto *{}", self.value(target));
+                self.line(depth, text);
+                None
+            }
+            Exit::Halt => {
+                self.line(depth, "halt".to_string());
+                None
+            }
+            Exit::Invalid(value) => {
+                self.line(depth, format!("invalid({})", value));
+                None
+            }
+        }
+    }
+
+    fn branch(
+        &mut self,
+        node: usize,
+        cond: Cond,
+        t: usize,
+        f: usize,
+        loops: &mut Vec<usize>,
+        depth: usize,
+    ) -> Option<usize> {
+        let mut join = self.graph.ipdom.get(&node).copied();
+
+        if let Some(header) = loops.last() {
+            if !join.is_some_and(|j| self.graph.loops[header].body.contains(&j)) {
+                join = None;
+            }
+        }
+
+        if join.is_none() {
+            // Prefer to fall through to a `continue` so it can be elided.
+            let jumps = (self.jump_to(t, loops), self.jump_to(f, loops));
+            let (cond, jump, next) = match jumps {
+                (Some(jump), Some(other)) if jump == "continue" => (cond.negate(), other, t),
+                (Some(jump), _) => (cond, jump, f),
+                (_, Some(jump)) => (cond.negate(), jump, t),
+                _ => {
+                    // Neither arm falls through, so the other can follow the
+                    // `if` rather than being nested in an `else`.
+                    self.line(depth, format!("if {} {{", self.cond(cond)));
+                    self.region(t, None, loops, depth + 1);
+                    self.line(depth, "}".to_string());
+                    return Some(f);
+                }
+            };
+
+            if jump.starts_with("goto") {
+                self.targets.insert(if next == f { t } else { f });
+            }
+
+            self.line(depth, format!("if {} {{", self.cond(cond)));
+            self.line(depth + 1, jump);
+            self.line(depth, "}".to_string());
+            return Some(next);
+        }
+
+        self.if_else(cond, t, f, join, loops, depth)
+    }
+
+    fn if_else(
+        &mut self,
+        cond: Cond,
+        t: usize,
+        f: usize,
+        join: Option<usize>,
+        loops: &mut Vec<usize>,
+        depth: usize,
+    ) -> Option<usize> {
+        let t = self.forward(t, join);
+        let f = self.forward(f, join);
+
+        let (cond, then, otherwise) = if join == Some(t) {
+            (cond.negate(), f, None)
+        } else if join == Some(f) {
+            (cond, t, None)
+        } else {
+            (cond, t, Some(f))
+        };
+
+        self.line(depth, format!("if {} {{", self.cond(cond)));
+        self.region(then, join, loops, depth + 1);
+
+        if let Some(otherwise) = otherwise {
+            self.line(depth, "} else {".to_string());
+            self.region(otherwise, join, loops, depth + 1);
+        }
+
+        self.line(depth, "}".to_string());
+        join
+    }
+
+    /// Skips over an empty block which only jumps to `join`, so it doesn't
+    /// produce an empty arm.
+    fn forward(&mut self, node: usize, join: Option<usize>) -> usize {
+        let block = &self.blocks[&node];
+
+        match block.exit {
+            Exit::Goto(target)
+                if Some(target) == join
+                    && block.stmts.is_empty()
+                    && self.graph.preds[&node] == 1
+                    && !self.graph.loops.contains_key(&node) =>
+            {
+                self.emitted.insert(node);
+                target
+            }
+            _ => node,
+        }
+    }
+
+    fn emit(mut self) -> Vec<Line> {
+        let mut loops = Vec::new();
+        self.region(self.function.body, None, &mut loops, 1);
+
+        // Anything only reachable through a `goto`.
+        for node in self.graph.nodes.clone() {
+            if !self.emitted.contains(&node) {
+                self.region(node, None, &mut loops, 1);
+            }
+        }
+
+        let targets = self.targets;
+        self.lines
+            .into_iter()
+            .filter_map(|mut line| match line.label {
+                Some(label) if targets.contains(&label) => {
+                    let label = format!("L{}:", label);
+                    line.text = if line.text.is_empty() {
+                        label
+                    } else {
+                        format!("{} {}", label, line.text)
+                    };
+                    Some(line)
+                }
+                _ if line.text.is_empty() => None,
+                _ => Some(line),
+            })
+            .collect()
+    }
+}
+
+fn name(entry: usize, functions: &BTreeMap<usize, Function>) -> String {
+    match functions.get(&entry) {
+        Some(f) if f.entry == 0 => "main".to_string(),
+        _ => format!("sub_{}", entry),
+    }
+}
+
+/// Decompiles `program` in to pseudo-code, one function after another starting
+/// with `main`.
+pub fn decompile(program: &[i64]) -> String {
+    let mut listing = Listing::discover(program);
+    let functions = listing.functions();
+    let blocks = listing.blocks();
+
+    let mut functions: BTreeMap<usize, Function> =
+        functions.into_iter().map(|f| (f.entry, f)).collect();
+
+    for block in blocks.values() {
+        for stmt in &block.stmts {
+            if let Stmt::Call(target, args) = stmt {
+                if let Some(f) = functions.get_mut(target) {
+                    let slots = (f.frame - 1).max(0) as usize;
+                    f.args = f.args.max(args.len()).min(slots);
+                }
+            }
+        }
+    }
+
+    let mut output = String::new();
+
+    for function in functions.values() {
+        let params: Vec<String> = (1..=function.args).map(|i| format!("arg{}", i)).collect();
+
+        if !output.is_empty() {
+            output.push('\n');
+        }
+
+        output += &format!(
+            "fn {}({}) {{\n",
+            name(function.entry, &functions),
+            params.join(", ")
+        );
+
+        let emitter = Emitter {
+            blocks: &blocks,
+            functions: &functions,
+            function,
+            graph: Graph::new(function.body, &blocks),
+            emitted: HashSet::new(),
+            targets: HashSet::new(),
+            lines: Vec::new(),
+        };
+
+        for line in emitter.emit() {
+            output += &format!("{}{}\n", "    ".repeat(line.depth), line.text);
+        }
+
+        output += "}\n";
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::parse_program;
+    use std::error::Error;
+
+    #[test]
+    fn quine() -> Result<(), Box<dyn Error>> {
+        let program = parse_program("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99")?;
+
+        let expected = "\
+fn main() {
+    loop {
+        rb += 1
+        output([rb-1])
+        [100] = [100] + 1
+        [101] = [100] == 16
+        if [101] != 0 {
+            break
+        }
+    }
+    halt
+}
+";
+
+        assert_eq!(decompile(&program), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn if_else() -> Result<(), Box<dyn Error>> {
+        let program = parse_program("3,100,1005,100,10,104,0,1105,1,12,104,1,99")?;
+
+        let expected = "\
+fn main() {
+    [100] = input()
+    if [100] != 0 {
+        output(1)
+    } else {
+        output(0)
+    }
+    halt
+}
+";
+
+        assert_eq!(decompile(&program), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn while_loop() -> Result<(), Box<dyn Error>> {
+        // Counts [100] down from the input, outputting each value.
+        let program = parse_program("3,100,1006,100,16,4,100,1001,100,-1,100,1105,1,2,0,0,99")?;
+
+        let expected = "\
+fn main() {
+    [100] = input()
+    while [100] != 0 {
+        output([100])
+        [100] = [100] - 1
+    }
+    halt
+}
+";
+
+        assert_eq!(decompile(&program), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn call() -> Result<(), Box<dyn Error>> {
+        // main passes 5 to a function which doubles its argument in place.
+        let program = parse_program(
+            "21101,5,0,1,21101,11,0,0,1105,1,14,204,1,99,109,2,22201,-1,-1,-1,109,-2,2105,1,0",
+        )?;
+
+        let expected = "\
+fn main() {
+    sub_14(5)
+    output([rb+1])
+    halt
+}
+
+fn sub_14(arg1) {
+    arg1 = arg1 + arg1
+    return
+}
+";
+
+        assert_eq!(decompile(&program), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_opcode() -> Result<(), Box<dyn Error>> {
+        let program = parse_program("1101,1,2,100,42")?;
+
+        let expected = "\
+fn main() {
+    [100] = 3
+    invalid(42)
+}
+";
+
+        assert_eq!(decompile(&program), expected);
+
+        Ok(())
+    }
+}
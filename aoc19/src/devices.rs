@@ -0,0 +1,191 @@
+//! Devices to attach to an intcode `Computer`'s memory.
+
+use crate::intcode::Device;
+use aoc_common::random::Xorshift;
+
+/// A grid of pixels, row by row. Non-zero pixels are lit.
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<i64>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Framebuffer {
+            width,
+            height,
+            pixels: vec![0; width * height],
+        }
+    }
+
+    /// The number of addresses to map the framebuffer over.
+    pub fn len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> i64 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Draws lit pixels as `#` like day 8 does.
+    pub fn render(&self) -> String {
+        let mut image = String::with_capacity((self.width + 1) * self.height);
+
+        for row in self.pixels.chunks(self.width) {
+            image.extend(row.iter().map(|&p| if p != 0 { '#' } else { ' ' }));
+            image.push('\n');
+        }
+
+        image
+    }
+}
+
+/// Attached over more than `len` addresses, reads past the last pixel are 0
+/// and writes there are dropped.
+impl Device for Framebuffer {
+    fn read(&mut self, offset: usize) -> i64 {
+        self.pixels.get(offset).copied().unwrap_or(0)
+    }
+
+    fn write(&mut self, offset: usize, value: i64) {
+        if let Some(pixel) = self.pixels.get_mut(offset) {
+            *pixel = value;
+        }
+    }
+}
+
+/// Counts the instructions executed since it was attached or last written to.
+#[derive(Default)]
+pub struct Clock {
+    cycles: i64,
+}
+
+impl Device for Clock {
+    fn read(&mut self, _offset: usize) -> i64 {
+        self.cycles
+    }
+
+    fn write(&mut self, _offset: usize, value: i64) {
+        self.cycles = value;
+    }
+
+    fn tick(&mut self) {
+        self.cycles += 1;
+    }
+}
+
+/// A xorshift generator. Every read returns a new non-negative number, writes
+/// reseed it.
+pub struct Random {
+    state: Xorshift,
+}
+
+impl Random {
+    pub fn new(seed: i64) -> Self {
+        Random {
+            state: Xorshift::new(seed as u64),
+        }
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Random::new(0)
+    }
+}
+
+impl Device for Random {
+    fn read(&mut self, _offset: usize) -> i64 {
+        (self.state.next_u64() >> 1) as i64
+    }
+
+    fn write(&mut self, _offset: usize, value: i64) {
+        self.state = Xorshift::new(value as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::{parse_program, Computer, StepResult};
+    use std::cell::RefCell;
+    use std::error::Error;
+    use std::rc::Rc;
+
+    #[test]
+    fn framebuffer() -> Result<(), Box<dyn Error>> {
+        // Lights (0, 0), (2, 0) and (1, 1), then reads (2, 0) back.
+        let program = parse_program("1101,0,1,100,1101,0,1,102,1101,0,1,104,4,102,99")?;
+        let screen = Rc::new(RefCell::new(Framebuffer::new(3, 2)));
+
+        let mut cpu = Computer::new(&program);
+        cpu.attach(100..106, screen.clone());
+
+        assert_eq!(cpu.run()?, StepResult::OutputAvailable(1));
+        assert_eq!(cpu.run()?, StepResult::Finished);
+        assert_eq!(screen.borrow().render(), "# #\n # \n");
+        assert_eq!(screen.borrow().pixel(1, 1), 1);
+
+        // The framebuffer isn't backed by memory.
+        assert!(cpu.memory().len() <= 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn framebuffer_past_the_end() -> Result<(), Box<dyn Error>> {
+        // Writes 7 just past the last pixel, then outputs what's there.
+        let program = parse_program("1101,0,7,102,4,102,99")?;
+        let screen = Rc::new(RefCell::new(Framebuffer::new(1, 2)));
+
+        let mut cpu = Computer::new(&program);
+        cpu.attach(100..110, screen.clone());
+
+        assert_eq!(cpu.run()?, StepResult::OutputAvailable(0));
+        assert_eq!(cpu.run()?, StepResult::Finished);
+        assert_eq!(screen.borrow().render(), " \n \n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn clock() -> Result<(), Box<dyn Error>> {
+        // Outputs the clock, resets it, copies it to 60 on the next cycle, then
+        // outputs the copy.
+        let program = parse_program("4,50,1101,0,0,50,1001,50,0,60,4,60,99")?;
+
+        let mut cpu = Computer::new(&program);
+        cpu.attach(50..51, Rc::new(RefCell::new(Clock::default())));
+
+        assert_eq!(cpu.run()?, StepResult::OutputAvailable(1));
+        assert_eq!(cpu.run()?, StepResult::OutputAvailable(1));
+        assert_eq!(cpu.run()?, StepResult::Finished);
+
+        Ok(())
+    }
+
+    #[test]
+    fn random() -> Result<(), Box<dyn Error>> {
+        // Seeds the generator with 7 then outputs two numbers.
+        let program = parse_program("1101,0,7,50,4,50,4,50,99")?;
+
+        let mut cpu = Computer::new(&program);
+        cpu.attach(50..51, Rc::new(RefCell::new(Random::default())));
+
+        let mut expected = Random::new(7);
+        let first = expected.read(0);
+        let second = expected.read(0);
+        assert_ne!(first, second);
+
+        assert_eq!(cpu.run()?, StepResult::OutputAvailable(first));
+        assert_eq!(cpu.run()?, StepResult::OutputAvailable(second));
+        assert!(first >= 0 && second >= 0);
+
+        Ok(())
+    }
+}
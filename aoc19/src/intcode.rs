@@ -1,6 +1,11 @@
+use aoc_common::parse;
+use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::convert::{TryFrom, TryInto};
 use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
 
 #[derive(Debug, PartialEq)]
 pub enum StepResult {
@@ -10,9 +15,9 @@ pub enum StepResult {
 }
 
 /// Modes are in respect to their operand order.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(u8)]
-enum Opcode {
+pub(crate) enum Opcode {
     Add(Mode, Mode, Mode),
     Multiply(Mode, Mode, Mode),
     Input(Mode),
@@ -28,8 +33,14 @@ enum Opcode {
 /// The `enum_primitive` crate would remove the need for this.
 /// Also interesting discussions here:
 /// https://internals.rust-lang.org/t/pre-rfc-adding-conversion-to-from-integer-on-enums-with-repr-i-u/8758
-impl From<i64> for Opcode {
-    fn from(v: i64) -> Self {
+impl TryFrom<i64> for Opcode {
+    type Error = String;
+
+    fn try_from(v: i64) -> Result<Self, Self::Error> {
+        if !(0..=99_999).contains(&v) {
+            return Err(format!("Unknown opcode: {}", v));
+        }
+
         let opstr = format!("{:5}", v);
 
         let mode_2: Option<Mode> = opstr[0..1].try_into().ok();
@@ -37,12 +48,13 @@ impl From<i64> for Opcode {
         let mode_0: Option<Mode> = opstr[2..3].try_into().ok();
 
         let kind = if &opstr[3..4] == " " {
-            opstr[4..].parse::<u8>().unwrap()
+            opstr[4..].parse::<u8>()
         } else {
-            opstr[3..].parse::<u8>().unwrap()
-        };
+            opstr[3..].parse::<u8>()
+        }
+        .map_err(|e| format!("Couldn't parse opcode {}: {}", v, e))?;
 
-        match kind {
+        let opcode = match kind {
             1 => Opcode::Add(
                 mode_0.unwrap_or(Mode::Indirect),
                 mode_1.unwrap_or(Mode::Indirect),
@@ -75,8 +87,10 @@ impl From<i64> for Opcode {
             ),
             9 => Opcode::ModifyBase(mode_0.unwrap_or(Mode::Indirect)),
             99 => Opcode::End,
-            _ => panic!("Unknown opcode: {}", kind),
-        }
+            _ => return Err(format!("Unknown opcode: {}", kind)),
+        };
+
+        Ok(opcode)
     }
 }
 
@@ -97,7 +111,7 @@ impl Opcode {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Mode {
     Indirect,
     Immediate,
@@ -112,24 +126,104 @@ impl TryFrom<&str> for Mode {
             "0" => Ok(Mode::Indirect),
             "1" => Ok(Mode::Immediate),
             "2" => Ok(Mode::Relative),
-            _ => Err(format!("Couldn't convert {} to a mode", c).into()),
+            _ => Err(format!("Couldn't convert {} to a mode", c)),
         }
     }
 }
 
-#[derive(Default)]
+/// Parses a comma separated program, ignoring surrounding whitespace.
+pub fn parse_program(input: &str) -> Result<Vec<i64>, Box<dyn Error>> {
+    Ok(parse::integers(input)?)
+}
+
+/// A store to a watched address.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryWrite {
+    /// The address of the instruction which made the write.
+    pub pc: usize,
+    pub addr: usize,
+    pub old: i64,
+    pub new: i64,
+}
+
+/// The differences between two computers, as `(left, right)` pairs.
+#[derive(Debug, Default, PartialEq)]
+pub struct Diff {
+    pub pc: Option<(usize, usize)>,
+    pub base: Option<(i64, i64)>,
+    /// `(address, left, right)` for each cell that differs. Memory which hasn't
+    /// been touched yet compares as 0.
+    pub memory: Vec<(usize, i64, i64)>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.pc.is_none() && self.base.is_none() && self.memory.is_empty()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((left, right)) = self.pc {
+            writeln!(f, "pc: {} -> {}", left, right)?;
+        }
+
+        if let Some((left, right)) = self.base {
+            writeln!(f, "base: {} -> {}", left, right)?;
+        }
+
+        for (addr, left, right) in &self.memory {
+            writeln!(f, "[{}]: {} -> {}", addr, left, right)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Something mapped in to a range of a computer's memory, see
+/// `Computer::attach`. Offsets are relative to the start of the range.
+pub trait Device {
+    fn read(&mut self, offset: usize) -> i64;
+    fn write(&mut self, offset: usize, value: i64);
+
+    /// Called once for each instruction the computer executes.
+    fn tick(&mut self) {}
+}
+
+/// Devices are shared so they can be inspected while attached.
+pub type SharedDevice = Rc<RefCell<dyn Device>>;
+
+/// The most memory a program can use, in words. Anything past it is far more
+/// likely a bad address than a program that really needs the memory.
+pub const MEMORY_LIMIT: usize = 1 << 24;
+
+/// Converts a value to an address, failing for negative addresses and ones
+/// past `MEMORY_LIMIT`.
+fn address(value: i64) -> Result<usize, String> {
+    usize::try_from(value)
+        .ok()
+        .filter(|&addr| addr < MEMORY_LIMIT)
+        .ok_or_else(|| format!("Address {} is outside memory", value))
+}
+
+#[derive(Clone, Default)]
 pub struct Computer<'a> {
     program: &'a [i64],
     inputs: VecDeque<i64>,
     mem: Vec<i64>,
     pc: usize,
     base: i64,
+    watches: Vec<Range<usize>>,
+    writes: Vec<MemoryWrite>,
+    devices: Vec<(Range<usize>, SharedDevice)>,
 }
 
 impl<'a> Computer<'a> {
     pub fn new(program: &'a [i64]) -> Self {
-        let mut cpu = Computer::default();
-        cpu.program = program;
+        let mut cpu = Computer {
+            program,
+            ..Default::default()
+        };
         cpu.reset();
         cpu
     }
@@ -139,117 +233,233 @@ impl<'a> Computer<'a> {
         self.mem = self.program.to_vec();
         self.pc = 0;
         self.base = 0;
+        self.writes.clear();
     }
 
-    pub fn add_input<'b>(&'b mut self, value: i64) -> &'b mut Self {
+    pub fn add_input(&mut self, value: i64) -> &mut Self {
         self.inputs.push_back(value);
         self
     }
 
+    /// Maps `device` over `range`, so loads and stores there go to the device
+    /// instead of memory. Keep a clone of the `Rc` to inspect the device later.
+    /// Stores to devices aren't reported by watches.
+    pub fn attach(&mut self, range: Range<usize>, device: SharedDevice) -> &mut Self {
+        self.devices.push((range, device));
+        self
+    }
+
     #[inline]
-    fn checked_load(&mut self, addr: usize) -> i64 {
-        if addr >= self.mem.len() {
+    fn device_at(&self, addr: usize) -> Option<(usize, &SharedDevice)> {
+        self.devices
+            .iter()
+            .find(|(range, _)| range.contains(&addr))
+            .map(|(range, device)| (addr - range.start, device))
+    }
+
+    #[inline]
+    fn checked_load(&mut self, addr: usize) -> Result<i64, String> {
+        if let Some((offset, device)) = self.device_at(addr) {
+            return Ok(device.borrow_mut().read(offset));
+        }
+
+        if addr >= MEMORY_LIMIT {
+            Err(format!("Address {} is outside memory", addr))
+        } else if addr >= self.mem.len() {
             self.mem.resize(addr, 0);
-            0
+            Ok(0)
         } else {
-            self.mem[addr]
+            Ok(self.mem[addr])
         }
     }
 
+    /// The address `base` plus the value at `offset` points to.
     #[inline]
-    pub fn load_value(&mut self, mode: &Mode, offset: usize) -> i64 {
+    fn relative_address(&mut self, offset: usize) -> Result<usize, String> {
+        let value = self.checked_load(offset)?;
+        let addr = self
+            .base
+            .checked_add(value)
+            .ok_or_else(|| format!("Address {} + {} is outside memory", self.base, value))?;
+        address(addr)
+    }
+
+    #[inline]
+    pub fn load_value(&mut self, mode: &Mode, offset: usize) -> Result<i64, String> {
         match mode {
             Mode::Indirect => {
-                let offset = self.checked_load(offset) as usize;
+                let offset = address(self.checked_load(offset)?)?;
                 self.checked_load(offset)
             }
             Mode::Immediate => self.checked_load(offset),
             Mode::Relative => {
-                let offset = (self.base + self.checked_load(offset)) as usize;
+                let offset = self.relative_address(offset)?;
                 self.checked_load(offset)
             }
         }
     }
 
     #[inline]
-    fn load_address(&mut self, mode: &Mode, offset: usize) -> usize {
+    fn load_address(&mut self, mode: &Mode, offset: usize) -> Result<usize, String> {
         match mode {
-            Mode::Indirect => self.load_value(mode, offset) as usize,
-            Mode::Immediate => self.load_value(mode, offset) as usize,
-            Mode::Relative => (self.base + self.checked_load(offset)) as usize,
+            Mode::Indirect => address(self.load_value(mode, offset)?),
+            Mode::Immediate => address(self.load_value(mode, offset)?),
+            Mode::Relative => self.relative_address(offset),
         }
     }
 
     #[inline]
-    pub fn store(&mut self, addr: usize, value: i64) {
-        if addr as usize >= self.mem.len() {
+    pub fn store(&mut self, addr: usize, value: i64) -> Result<(), String> {
+        if let Some((offset, device)) = self.device_at(addr) {
+            device.borrow_mut().write(offset, value);
+            return Ok(());
+        }
+
+        if addr >= MEMORY_LIMIT {
+            return Err(format!("Address {} is outside memory", addr));
+        }
+
+        if addr >= self.mem.len() {
             self.mem.resize(addr + 1, 0);
         }
 
-        self.mem[addr as usize] = value;
+        if self.watches.iter().any(|r| r.contains(&addr)) {
+            self.writes.push(MemoryWrite {
+                pc: self.pc,
+                addr,
+                old: self.mem[addr],
+                new: value,
+            });
+        }
+
+        self.mem[addr] = value;
+        Ok(())
+    }
+
+    /// Records every store to `range` from now on, see `writes`.
+    pub fn watch(&mut self, range: Range<usize>) -> &mut Self {
+        self.watches.push(range);
+        self
+    }
+
+    /// The stores to watched addresses, oldest first.
+    pub fn writes(&self) -> &[MemoryWrite] {
+        &self.writes
+    }
+
+    pub fn take_writes(&mut self) -> Vec<MemoryWrite> {
+        std::mem::take(&mut self.writes)
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn base(&self) -> i64 {
+        self.base
+    }
+
+    pub fn memory(&self) -> &[i64] {
+        &self.mem
+    }
+
+    /// Compares this computer's state against `other`'s. Snapshot a computer
+    /// with `clone` to see what a call to `run` changed.
+    pub fn diff(&self, other: &Computer) -> Diff {
+        let mut diff = Diff::default();
+
+        if self.pc != other.pc {
+            diff.pc = Some((self.pc, other.pc));
+        }
+
+        if self.base != other.base {
+            diff.base = Some((self.base, other.base));
+        }
+
+        for addr in 0..self.mem.len().max(other.mem.len()) {
+            let left = self.mem.get(addr).copied().unwrap_or(0);
+            let right = other.mem.get(addr).copied().unwrap_or(0);
+
+            if left != right {
+                diff.memory.push((addr, left, right));
+            }
+        }
+
+        diff
     }
 
     /// Runs until I/O is required, or the program has ended.
     pub fn run(&mut self) -> Result<StepResult, Box<dyn Error>> {
         loop {
-            let opcode: Opcode = self.mem[self.pc].into();
+            let opcode = Opcode::try_from(self.checked_load(self.pc)?)?;
+
+            if let Opcode::Input(_) = opcode {
+                if self.inputs.is_empty() {
+                    return Ok(StepResult::NeedInput);
+                }
+            }
+
+            for (_, device) in &self.devices {
+                device.borrow_mut().tick();
+            }
 
             match &opcode {
                 Opcode::Add(left_mode, right_mode, dest_mode) => {
-                    let left_value = self.load_value(&left_mode, self.pc + 1);
-                    let right_value = self.load_value(&right_mode, self.pc + 2);
-                    let dest = self.load_address(&dest_mode, self.pc + 3) as usize;
-                    self.store(dest, left_value + right_value);
+                    let left_value = self.load_value(left_mode, self.pc + 1)?;
+                    let right_value = self.load_value(right_mode, self.pc + 2)?;
+                    let dest = self.load_address(dest_mode, self.pc + 3)?;
+                    self.store(dest, left_value + right_value)?;
                 }
                 Opcode::Multiply(left_mode, right_mode, dest_mode) => {
-                    let left_value = self.load_value(&left_mode, self.pc + 1);
-                    let right_value = self.load_value(&right_mode, self.pc + 2);
-                    let dest = self.load_address(&dest_mode, self.pc + 3) as usize;
-                    self.store(dest, left_value * right_value);
+                    let left_value = self.load_value(left_mode, self.pc + 1)?;
+                    let right_value = self.load_value(right_mode, self.pc + 2)?;
+                    let dest = self.load_address(dest_mode, self.pc + 3)?;
+                    self.store(dest, left_value * right_value)?;
                 }
                 Opcode::Input(mode) => {
-                    if self.inputs.is_empty() {
-                        return Ok(StepResult::NeedInput);
-                    }
-                    let dest = self.load_address(&mode, self.pc + 1);
+                    let dest = self.load_address(mode, self.pc + 1)?;
                     let input = self.inputs.pop_front().unwrap();
-                    self.store(dest, input);
+                    self.store(dest, input)?;
                 }
                 Opcode::Output(mode) => {
-                    let value = self.load_value(&mode, self.pc + 1);
+                    let value = self.load_value(mode, self.pc + 1)?;
                     self.pc += opcode.len();
                     return Ok(StepResult::OutputAvailable(value));
                 }
                 Opcode::JumpIfTrue(value_mode, dest_mode) => {
-                    let value = self.load_value(&value_mode, self.pc + 1);
+                    let value = self.load_value(value_mode, self.pc + 1)?;
                     if value != 0 {
-                        self.pc = self.load_value(&dest_mode, self.pc + 2) as usize;
+                        self.pc = address(self.load_value(dest_mode, self.pc + 2)?)?;
                         continue;
                     }
                 }
                 Opcode::JumpIfFalse(value_mode, dest_mode) => {
-                    let value = self.load_value(&value_mode, self.pc + 1);
+                    let value = self.load_value(value_mode, self.pc + 1)?;
                     if value == 0 {
-                        self.pc = self.load_value(&dest_mode, self.pc + 2) as usize;
+                        self.pc = address(self.load_value(dest_mode, self.pc + 2)?)?;
                         continue;
                     }
                 }
                 Opcode::IsLess(left_mode, right_mode, dest_mode) => {
-                    let left_value = self.load_value(&left_mode, self.pc + 1);
-                    let right_value = self.load_value(&right_mode, self.pc + 2);
-                    let dest = self.load_address(&dest_mode, self.pc + 3) as usize;
+                    let left_value = self.load_value(left_mode, self.pc + 1)?;
+                    let right_value = self.load_value(right_mode, self.pc + 2)?;
+                    let dest = self.load_address(dest_mode, self.pc + 3)?;
                     let result = if left_value < right_value { 1 } else { 0 };
-                    self.store(dest, result);
+                    self.store(dest, result)?;
                 }
                 Opcode::IsEqual(left_mode, right_mode, dest_mode) => {
-                    let left = self.load_value(&left_mode, self.pc + 1);
-                    let right = self.load_value(&right_mode, self.pc + 2);
-                    let dest = self.load_address(&dest_mode, self.pc + 3) as usize;
+                    let left = self.load_value(left_mode, self.pc + 1)?;
+                    let right = self.load_value(right_mode, self.pc + 2)?;
+                    let dest = self.load_address(dest_mode, self.pc + 3)?;
                     let result = if left == right { 1 } else { 0 };
-                    self.store(dest, result);
+                    self.store(dest, result)?;
                 }
                 Opcode::ModifyBase(mode) => {
-                    self.base += self.load_value(&mode, self.pc + 1);
+                    let offset = self.load_value(mode, self.pc + 1)?;
+                    self.base = self
+                        .base
+                        .checked_add(offset)
+                        .ok_or("The relative base overflowed")?;
                 }
                 Opcode::End => {
                     return Ok(StepResult::Finished);
@@ -260,3 +470,81 @@ impl<'a> Computer<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff() -> Result<(), Box<dyn Error>> {
+        // Stores the input doubled at 9, then adjusts the base.
+        let program = parse_program("3,9,1002,9,2,9,109,5,99,0")?;
+
+        let mut cpu = Computer::new(&program);
+        let before = cpu.clone();
+        assert!(before.diff(&cpu).is_empty());
+
+        cpu.add_input(21);
+        assert_eq!(cpu.run()?, StepResult::Finished);
+
+        let diff = before.diff(&cpu);
+        assert_eq!(diff.pc, Some((0, 8)));
+        assert_eq!(diff.base, Some((0, 5)));
+        assert_eq!(diff.memory, vec![(9, 0, 42)]);
+        assert_eq!(diff.to_string(), "pc: 0 -> 8\nbase: 0 -> 5\n[9]: 0 -> 42\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn watch() -> Result<(), Box<dyn Error>> {
+        // Counts [20] up to 3 while writing comparison results to [21].
+        let program = parse_program("1001,20,1,20,1007,20,3,21,1005,21,0,99")?;
+
+        let mut cpu = Computer::new(&program);
+        cpu.watch(20..21);
+        assert_eq!(cpu.run()?, StepResult::Finished);
+
+        let writes: Vec<(usize, i64, i64)> =
+            cpu.writes().iter().map(|w| (w.pc, w.old, w.new)).collect();
+        assert_eq!(writes, vec![(0, 0, 1), (0, 1, 2), (0, 2, 3)]);
+        assert!(cpu.writes().iter().all(|w| w.addr == 20));
+
+        assert_eq!(cpu.take_writes().len(), 3);
+        assert!(cpu.writes().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn bad_addresses() -> Result<(), Box<dyn Error>> {
+        for (program, error, pc) in [
+            ("1,-1,0,0,99", "Address -1 is outside memory", 0),
+            (
+                "1101,1,1,99999999999,99",
+                "Address 99999999999 is outside memory",
+                0,
+            ),
+            ("109,-5,22201,0,0,0,99", "Address -5 is outside memory", 2),
+            ("1105,1,-3", "Address -3 is outside memory", 0),
+            (
+                "109,9223372036854775807,209,1,99",
+                "Address 9223372036854775807 + 1 is outside memory",
+                2,
+            ),
+        ] {
+            let program = parse_program(program)?;
+            let mut cpu = Computer::new(&program);
+            assert_eq!(cpu.run().map_err(|e| e.to_string()), Err(error.to_string()));
+            assert_eq!(cpu.pc(), pc);
+        }
+
+        let program = parse_program("99")?;
+        let mut cpu = Computer::new(&program);
+        assert!(cpu.store(MEMORY_LIMIT, 1).is_err());
+        cpu.store(MEMORY_LIMIT - 1, 1)?;
+        assert_eq!(cpu.load_value(&Mode::Immediate, MEMORY_LIMIT - 1)?, 1);
+
+        Ok(())
+    }
+}
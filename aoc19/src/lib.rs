@@ -0,0 +1,51 @@
+use aoc_common::Registry;
+
+pub mod asteroids;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod decompile;
+pub mod devices;
+pub mod intcode;
+pub mod sif;
+
+pub const YEAR: u16 = 2019;
+
+pub fn register(registry: &mut Registry) {
+    registry
+        .set_dir(YEAR, env!("CARGO_MANIFEST_DIR"))
+        .add::<day1::Day1>(YEAR, 1, day1::INPUT)
+        .add::<day2::Day2>(YEAR, 2, day2::INPUT)
+        .add::<day3::Day3>(YEAR, 3, day3::INPUT)
+        .add::<day4::Day4>(YEAR, 4, day4::INPUT)
+        .add::<day5::Day5>(YEAR, 5, day5::INPUT)
+        .add::<day6::Day6>(YEAR, 6, day6::INPUT)
+        .add::<day7::Day7>(YEAR, 7, day7::INPUT)
+        .add::<day8::Day8>(YEAR, 8, day8::INPUT)
+        .add::<day9::Day9>(YEAR, 9, day9::INPUT)
+        .add::<day10::Day10>(YEAR, 10, day10::INPUT)
+        .add::<day11::Day11>(YEAR, 11, day11::INPUT);
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_common::{runner, Registry};
+
+    #[test]
+    fn examples() {
+        let mut registry = Registry::new();
+        super::register(&mut registry);
+
+        if let Err(failures) = runner::check_examples(&registry) {
+            panic!("\n{}", failures);
+        }
+    }
+}
@@ -1,32 +1,9 @@
-#![feature(test)]
-extern crate test;
+use aoc_common::{runner, Registry};
 
-use std::error::Error;
+fn main() {
+    let mut registry = Registry::new();
+    aoc18::register(&mut registry);
+    aoc19::register(&mut registry);
 
-mod day1;
-mod day10;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod intcode;
-
-fn main() -> Result<(), Box<dyn Error>> {
-    /*
-    day1::solve();
-    day2::solve();
-    day3::solve();
-    day4::solve();
-    day5::solve()?;
-    day6::solve();
-    day7::solve()?;
-    day8::solve();
-    day9::solve()?;
-    */
-    day10::solve();
-    Ok(())
+    runner::main("aoc19", &registry);
 }
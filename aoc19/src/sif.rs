@@ -0,0 +1,425 @@
+//! The Space Image Format from day 8: layers of `width * height` digits, where
+//! `0` is black, `1` is white and `2` is transparent. The first layer is in
+//! front.
+
+use aoc_common::image::{self, Format, Rgb};
+use aoc_common::random::Xorshift;
+use aoc_common::{Grid, ParseError};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Pixel {
+    Black,
+    White,
+    Transparent,
+}
+
+impl Pixel {
+    /// How the pixel is drawn when exported, transparent pixels are grey.
+    pub fn rgb(self) -> Rgb {
+        match self {
+            Pixel::Black => [0, 0, 0],
+            Pixel::White => [255, 255, 255],
+            Pixel::Transparent => [128, 128, 128],
+        }
+    }
+}
+
+impl TryFrom<char> for Pixel {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '0' => Ok(Pixel::Black),
+            '1' => Ok(Pixel::White),
+            '2' => Ok(Pixel::Transparent),
+            _ => Err(format!("Unknown pixel colour {:?}", c)),
+        }
+    }
+}
+
+impl fmt::Display for Pixel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digit = match self {
+            Pixel::Black => '0',
+            Pixel::White => '1',
+            Pixel::Transparent => '2',
+        };
+
+        write!(f, "{}", digit)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    /// Front to back.
+    layers: Vec<Grid<Pixel>>,
+}
+
+impl Image {
+    /// Decodes the digits, ignoring surrounding whitespace. Fails unless there
+    /// are one or more whole layers.
+    pub fn decode(data: &str, width: usize, height: usize) -> Result<Self, Box<dyn Error>> {
+        if width == 0 || height == 0 {
+            return Err(format!("A {}x{} image has no pixels", width, height).into());
+        }
+
+        let mut pixels = Vec::new();
+
+        for (i, line) in data.trim().lines().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                let pixel = Pixel::try_from(c).map_err(|e| {
+                    ParseError::new(
+                        i + 1,
+                        &c.to_string(),
+                        format!("{} in column {}", e, column + 1),
+                    )
+                })?;
+                pixels.push(pixel);
+            }
+        }
+
+        let area = width * height;
+        if pixels.is_empty() || pixels.len() % area != 0 {
+            return Err(format!(
+                "{} pixels isn't a whole number of {}x{} layers",
+                pixels.len(),
+                width,
+                height
+            )
+            .into());
+        }
+
+        let layers = pixels
+            .chunks(area)
+            .map(|layer| Grid::from_cells(width, height, layer.to_vec()))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Image {
+            width,
+            height,
+            layers,
+        })
+    }
+
+    /// An image of the given layers, front to back. Fails unless there's at
+    /// least one layer and they're all the same size.
+    pub fn new(layers: Vec<Grid<Pixel>>) -> Result<Self, String> {
+        let first = layers.first().ok_or("An image needs at least one layer")?;
+        let (width, height) = (first.width(), first.height());
+
+        if width == 0 || height == 0 {
+            return Err(format!("A {}x{} image has no pixels", width, height));
+        }
+
+        if let Some((i, layer)) = layers
+            .iter()
+            .enumerate()
+            .find(|(_, l)| (l.width(), l.height()) != (width, height))
+        {
+            return Err(format!(
+                "Layer {} is {}x{}, expected {}x{}",
+                i + 1,
+                layer.width(),
+                layer.height(),
+                width,
+                height
+            ));
+        }
+
+        Ok(Image {
+            width,
+            height,
+            layers,
+        })
+    }
+
+    /// Splits `pixels` into `depth` layers which composite back to it. Each
+    /// pixel is placed on a random layer, with transparent pixels in front of
+    /// it and random black or white ones behind it, so that decoding has to
+    /// handle transparency properly. The same seed gives the same layers.
+    pub fn layered(pixels: &Grid<Pixel>, depth: usize, seed: i64) -> Result<Self, String> {
+        if depth == 0 {
+            return Err("An image needs at least one layer".to_string());
+        }
+
+        let mut random = Xorshift::new(seed as u64);
+        let mut layers =
+            vec![Grid::new(pixels.width(), pixels.height(), Pixel::Transparent); depth];
+
+        for (pos, &pixel) in pixels.iter() {
+            if pixel == Pixel::Transparent {
+                continue;
+            }
+
+            let front = random.below(depth as u64) as usize;
+            layers[front][pos] = pixel;
+
+            for layer in &mut layers[front + 1..] {
+                layer[pos] = match random.below(3) {
+                    0 => Pixel::Black,
+                    1 => Pixel::White,
+                    _ => Pixel::Transparent,
+                };
+            }
+        }
+
+        Image::new(layers)
+    }
+
+    /// The digits of every layer on a single line, as `decode` reads them.
+    pub fn encode(&self) -> String {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.iter().map(|(_, p)| p.to_string()))
+            .collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The layers, front to back.
+    pub fn layers(&self) -> &[Grid<Pixel>] {
+        &self.layers
+    }
+
+    /// How many pixels of the layer are the given colour.
+    pub fn count(layer: &Grid<Pixel>, colour: Pixel) -> usize {
+        layer.iter().filter(|(_, &p)| p == colour).count()
+    }
+
+    /// The number of white pixels times the number of transparent pixels in
+    /// the layer with the fewest black pixels, to check an image isn't
+    /// corrupted.
+    pub fn checksum(&self) -> usize {
+        let layer = self
+            .layers
+            .iter()
+            .min_by_key(|layer| Self::count(layer, Pixel::Black))
+            .expect("Images have at least one layer");
+
+        Self::count(layer, Pixel::White) * Self::count(layer, Pixel::Transparent)
+    }
+
+    /// Each pixel is the frontmost one that isn't transparent, or transparent
+    /// if they all are.
+    pub fn composite(&self) -> Grid<Pixel> {
+        let mut image = Grid::new(self.width, self.height, Pixel::Transparent);
+
+        for layer in self.layers.iter().rev() {
+            for (pos, &pixel) in layer.iter() {
+                if pixel != Pixel::Transparent {
+                    image[pos] = pixel;
+                }
+            }
+        }
+
+        image
+    }
+
+    /// Writes `composite.<ext>` and `layer<n>.<ext>` for each layer into
+    /// `dir`, with each pixel drawn as a `scale` by `scale` square. Returns
+    /// the paths written.
+    pub fn export(
+        &self,
+        dir: &Path,
+        format: Format,
+        scale: usize,
+    ) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        std::fs::create_dir_all(dir)?;
+
+        let images = std::iter::once(("composite".to_string(), self.composite())).chain(
+            self.layers
+                .iter()
+                .enumerate()
+                .map(|(i, layer)| (format!("layer{}", i + 1), layer.clone())),
+        );
+
+        let mut paths = Vec::new();
+
+        for (name, pixels) in images {
+            let path = dir.join(format!("{}.{}", name, format.extension()));
+            image::save(&image::scale(&pixels.map(|p| p.rgb()), scale), &path)?;
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+}
+
+/// Verifies `data` is a valid `width` by `height` image and returns its
+/// checksum.
+pub fn checksum(data: &str, width: usize, height: usize) -> Result<usize, Box<dyn Error>> {
+    Ok(Image::decode(data, width, height)?.checksum())
+}
+
+/// White for lit pixels and black for the rest.
+pub fn from_lit(lit: &Grid<bool>) -> Grid<Pixel> {
+    lit.map(|&l| if l { Pixel::White } else { Pixel::Black })
+}
+
+/// Reads pixels drawn as text, the inverse of `render`. Short lines are padded
+/// with black.
+pub fn parse_art(art: &str) -> Result<Grid<Pixel>, ParseError> {
+    let width = art.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut pixels = Vec::new();
+
+    for (i, line) in art.lines().enumerate() {
+        for (column, c) in line.chars().enumerate() {
+            pixels.push(match c {
+                ' ' => Pixel::Black,
+                '#' => Pixel::White,
+                '.' => Pixel::Transparent,
+                _ => {
+                    let message = format!("Expected ' ', '#' or '.' in column {}", column + 1);
+                    return Err(ParseError::new(i + 1, &c.to_string(), message));
+                }
+            });
+        }
+        pixels.extend(std::iter::repeat_n(
+            Pixel::Black,
+            width - line.chars().count(),
+        ));
+    }
+
+    let height = art.lines().count();
+    Ok(Grid::from_cells(width, height, pixels).expect("Every line is padded to the width"))
+}
+
+/// Lit pixels for OCR, transparent ones count as black.
+pub fn lit(pixels: &Grid<Pixel>) -> Grid<bool> {
+    pixels.map(|&p| p == Pixel::White)
+}
+
+/// Draws pixels as text, `#` for white, a space for black and `.` for
+/// transparent.
+pub fn render(pixels: &Grid<Pixel>) -> String {
+    pixels.render(|p| match p {
+        Pixel::Black => ' ',
+        Pixel::White => '#',
+        Pixel::Transparent => '.',
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::{ocr, Point2};
+
+    #[test]
+    fn decodes() -> Result<(), Box<dyn Error>> {
+        let image = Image::decode("0222112222120000\n", 2, 2)?;
+        assert_eq!(image.layers().len(), 4);
+        assert_eq!(image.layers()[1][Point2::new(0, 0)], Pixel::White);
+        assert_eq!(render(&image.composite()), " #\n# \n");
+        // The second layer has no black pixels, two white and two transparent.
+        assert_eq!(image.checksum(), 4);
+
+        let image = Image::decode("123456789012", 3, 2);
+        assert_eq!(
+            image.unwrap_err().to_string(),
+            "line 1: Unknown pixel colour '3' in column 3: \"3\""
+        );
+
+        assert!(Image::decode("0120", 3, 2).is_err());
+        assert!(Image::decode("", 3, 2).is_err());
+        assert!(Image::decode("0", 0, 2).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transparency() -> Result<(), Box<dyn Error>> {
+        let image = Image::decode("2212", 2, 1)?;
+        assert_eq!(render(&image.composite()), "#.\n");
+        assert_eq!(
+            lit(&image.composite()).render(|&l| if l { '#' } else { '.' }),
+            "#.\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn encodes() -> Result<(), Box<dyn Error>> {
+        let image = Image::decode("0222112222120000", 2, 2)?;
+        assert_eq!(image.encode(), "0222112222120000");
+
+        let pixels = parse_art(" #\n#.\n")?;
+        assert_eq!(render(&pixels), " #\n#.\n");
+        assert_eq!(Image::new(vec![pixels.clone()])?.encode(), "0112");
+        assert_eq!(render(&parse_art("#\n##")?), "# \n##\n");
+        assert!(parse_art("#x").is_err());
+
+        let layered = Image::layered(&pixels, 5, 1)?;
+        assert_eq!(layered.layers().len(), 5);
+        assert_eq!(layered.composite(), pixels);
+        assert_eq!(Image::decode(&layered.encode(), 2, 2)?, layered);
+        assert_eq!(Image::layered(&pixels, 5, 1)?, layered);
+
+        assert!(Image::new(vec![]).is_err());
+        assert!(Image::new(vec![Grid::new(2, 1, Pixel::Black), pixels]).is_err());
+        assert!(Image::layered(&parse_art("#")?, 0, 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trips_text() -> Result<(), Box<dyn Error>> {
+        let pixels = from_lit(&ocr::render("HELLO")?);
+
+        for seed in 1..10 {
+            let image = Image::layered(&pixels, 50, seed)?;
+            let decoded = Image::decode(&image.encode(), pixels.width(), 6)?;
+            assert_eq!(ocr::recognise(&lit(&decoded.composite()))?, "HELLO");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn checksums() -> Result<(), Box<dyn Error>> {
+        // The layer with the fewest zeros is the third, with two ones and a
+        // two.
+        assert_eq!(checksum("001201112", 3, 1)?, 2);
+        // As a single layer there are four ones and two twos.
+        assert_eq!(checksum("001201112", 9, 1)?, 8);
+        assert!(checksum("0012", 3, 1).is_err());
+        assert!(checksum("0013", 2, 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn exports() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("aoc-sif-{}", std::process::id()));
+        let image = Image::decode("0222112222120000", 2, 2)?;
+
+        let paths = image.export(&dir, Format::Pgm, 3)?;
+        assert_eq!(paths.len(), 5);
+        assert_eq!(paths[0], dir.join("composite.pgm"));
+        assert_eq!(paths[4], dir.join("layer4.pgm"));
+
+        let composite = std::fs::read(&paths[0])?;
+        assert!(composite.starts_with(b"P5\n6 6\n255\n"));
+        assert_eq!(composite.len(), b"P5\n6 6\n255\n".len() + 36);
+        // The top left pixel is black and the top right is white.
+        assert_eq!(composite[11..17], [0, 0, 0, 255, 255, 255]);
+
+        image.export(&dir, Format::Png, 1)?;
+        assert!(std::fs::read(dir.join("layer1.png"))?.starts_with(b"\x89PNG"));
+
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}